@@ -0,0 +1,336 @@
+//! flock statistics per tick, plotted over time and exported as CSV
+
+use std::{collections::VecDeque, fmt::Write as _};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_inspector_egui::{bevy_egui::EguiContexts, egui};
+
+use crate::{
+    components::{Boid, Collider, Velocity},
+    replay::Replay,
+    resources::{BoidUniverse, SimTick},
+    spatial::SpatialIndex,
+    systems::advance_tick,
+    PHYISCS_TICK_RATE,
+};
+
+/// a minute of ticks kept for the plots and the export
+pub const HISTORY_LEN: usize = 60 * PHYISCS_TICK_RATE as usize;
+
+/// how far to look for a nearest neighbour, boids without one in reach
+/// don't count towards the nearest neighbour distances
+const NEAREST_SEARCH: f32 = 100.0;
+
+pub struct AnalyticsPlugin;
+
+impl Plugin for AnalyticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Analytics::default());
+        app.add_systems(
+            FixedUpdate,
+            record_analytics
+                .after(advance_tick)
+                .run_if(|analytics: Res<Analytics>| analytics.enabled)
+                .run_if(not(resource_exists::<Replay>())),
+        );
+        app.add_systems(Update, analytics_window);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FlockStats {
+    pub tick: u64,
+    pub boids: usize,
+    /// length of the mean heading, 1 when every boid flies the same way
+    pub polarization: f32,
+    /// mean rotation around the flock centroid, 1 for a perfect mill
+    pub angular_momentum: f32,
+    pub mean_nearest: f32,
+    pub min_nearest: f32,
+    /// mean `Collider::nearby`
+    pub mean_nearby: f32,
+    /// groups of two or more boids linked by chains within the cohesion
+    /// radius, boids on their own don't count
+    pub flocks: usize,
+}
+
+impl FlockStats {
+    pub const CSV_HEADER: &'static str =
+        "tick,boids,polarization,angular_momentum,mean_nearest,min_nearest,mean_nearby,flocks";
+
+    pub fn csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{}",
+            self.tick,
+            self.boids,
+            self.polarization,
+            self.angular_momentum,
+            self.mean_nearest,
+            self.min_nearest,
+            self.mean_nearby,
+            self.flocks
+        )
+    }
+}
+
+pub fn measure<'a>(
+    universe: &BoidUniverse,
+    boid_index: &SpatialIndex<Boid>,
+    tick: u64,
+    boids: impl Iterator<Item = (Entity, &'a Transform, &'a Velocity, &'a Collider)>,
+) -> FlockStats {
+    let boids: Vec<_> = boids.collect();
+    let count = boids.len();
+    if count == 0 {
+        return FlockStats {
+            tick,
+            ..Default::default()
+        };
+    }
+    let per_boid = count as f32;
+
+    let centroid = boids.iter().fold(Vec3::ZERO, |acc, (_, transform, ..)| {
+        acc + transform.translation
+    }) / per_boid;
+    let (heading, milling, nearby) = boids.iter().fold(
+        (Vec3::ZERO, 0.0, 0),
+        |(heading, milling, nearby), (_, transform, velocity, collider)| {
+            let direction = velocity.value.normalize_or_zero();
+            let radial = (transform.translation - centroid).normalize_or_zero();
+            (
+                heading + direction,
+                milling + radial.x * direction.y - radial.y * direction.x,
+                nearby + collider.nearby,
+            )
+        },
+    );
+
+    let linked: Vec<_> = boids
+        .iter()
+        .map(|(entity, transform, _, collider)| (*entity, *transform, *collider))
+        .collect();
+    let (nearest, mut flocks) = link_flocks(universe, boid_index, &linked);
+    let mut sizes: HashMap<usize, usize> = HashMap::new();
+    (0..count).for_each(|index| *sizes.entry(flocks.find(index)).or_default() += 1);
+
+    FlockStats {
+        tick,
+        boids: count,
+        polarization: heading.length() / per_boid,
+        angular_momentum: milling.abs() / per_boid,
+        mean_nearest: match nearest.is_empty() {
+            true => 0.0,
+            false => nearest.iter().sum::<f32>() / nearest.len() as f32,
+        },
+        min_nearest: nearest.iter().copied().reduce(f32::min).unwrap_or(0.0),
+        mean_nearby: nearby as f32 / per_boid,
+        flocks: sizes.values().filter(|size| **size > 1).count(),
+    }
+}
+
+/// an id per boid, the same for boids linked within the cohesion radius
+pub fn flock_labels(
+    universe: &BoidUniverse,
+    boid_index: &SpatialIndex<Boid>,
+    boids: &[(Entity, &Transform, &Collider)],
+) -> Vec<usize> {
+    let (_, mut flocks) = link_flocks(universe, boid_index, boids);
+    (0..boids.len()).map(|index| flocks.find(index)).collect()
+}
+
+/// one tree query per boid, giving the nearest neighbour distances and
+/// the boids linked within the cohesion radius
+fn link_flocks(
+    universe: &BoidUniverse,
+    boid_index: &SpatialIndex<Boid>,
+    boids: &[(Entity, &Transform, &Collider)],
+) -> (Vec<f32>, UnionFind) {
+    let index: HashMap<Entity, usize> = boids
+        .iter()
+        .enumerate()
+        .map(|(index, (entity, ..))| (*entity, index))
+        .collect();
+    let link = universe.cohesion_radius;
+    let reach = link.max(NEAREST_SEARCH);
+
+    let mut flocks = UnionFind::new(boids.len());
+    let mut nearest = Vec::with_capacity(boids.len());
+    boids
+        .iter()
+        .enumerate()
+        .for_each(|(this, (entity, transform, collider))| {
+            let position = transform.translation;
+            let region = collider.into_region(position).with_margin(reach as i32);
+
+            let closest =
+                boid_index
+                    .query(&region, &[*entity])
+                    .iter()
+                    .fold(f32::MAX, |closest, body| {
+                        let distance = body.position.distance(position);
+                        if distance <= link {
+                            if let Some(other) = index.get(&body.entity) {
+                                flocks.union(this, *other);
+                            }
+                        }
+                        closest.min(distance)
+                    });
+            if closest <= NEAREST_SEARCH {
+                nearest.push(closest);
+            }
+        });
+
+    (nearest, flocks)
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let mut root = index;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = index;
+        while self.parents[current] != root {
+            let next = self.parents[current];
+            self.parents[current] = root;
+            current = next;
+        }
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents[b.max(a)] = a.min(b);
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Analytics {
+    pub enabled: bool,
+    pub history: VecDeque<FlockStats>,
+    pub path: String,
+    pub status: Option<String>,
+}
+
+impl Default for Analytics {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            path: "analytics.csv".to_string(),
+            status: None,
+        }
+    }
+}
+
+impl Analytics {
+    pub fn push(&mut self, stats: FlockStats) {
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(stats);
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(FlockStats::CSV_HEADER);
+        csv.push('\n');
+        self.history.iter().for_each(|stats| {
+            let _ = writeln!(csv, "{}", stats.csv_row());
+        });
+        csv
+    }
+}
+
+fn record_analytics(
+    query: Query<(Entity, &Transform, &Velocity, &Collider), With<Boid>>,
+    universe: Res<BoidUniverse>,
+    boid_index: Res<SpatialIndex<Boid>>,
+    tick: Res<SimTick>,
+    mut analytics: ResMut<Analytics>,
+) {
+    analytics.push(measure(&universe, &boid_index, tick.0, query.iter()));
+}
+
+fn analytics_window(mut context: EguiContexts, mut analytics: ResMut<Analytics>) {
+    egui::Window::new("Analytics")
+        .anchor(egui::Align2::RIGHT_TOP, egui::Vec2::new(0.0, 0.0))
+        .default_open(false)
+        .show(context.ctx_mut(), |ui| {
+            ui.checkbox(&mut analytics.enabled, "record every tick");
+
+            let series = |value: fn(&FlockStats) -> f32| -> Vec<f32> {
+                analytics.history.iter().map(value).collect()
+            };
+            plot(ui, "polarization", &series(|s| s.polarization));
+            plot(ui, "angular momentum", &series(|s| s.angular_momentum));
+            plot(ui, "mean nearest", &series(|s| s.mean_nearest));
+            plot(ui, "min nearest", &series(|s| s.min_nearest));
+            plot(ui, "mean nearby", &series(|s| s.mean_nearby));
+            plot(ui, "flocks", &series(|s| s.flocks as f32));
+            plot(ui, "boids", &series(|s| s.boids as f32));
+
+            ui.horizontal(|ui| {
+                ui.text_edit_singleline(&mut analytics.path);
+                if ui.button("export csv").clicked() {
+                    analytics.status =
+                        Some(match std::fs::write(&analytics.path, analytics.to_csv()) {
+                            Ok(()) => format!("wrote {} ticks", analytics.history.len()),
+                            Err(err) => format!("can't write {}: {}", analytics.path, err),
+                        });
+                }
+                if ui.button("clear").clicked() {
+                    analytics.history.clear();
+                }
+            });
+            if let Some(status) = &analytics.status {
+                ui.label(status);
+            }
+        });
+}
+
+/// a line over the history, scaled to its own range
+pub fn plot(ui: &mut egui::Ui, label: &str, values: &[f32]) {
+    let current = values.last().copied().unwrap_or_default();
+    ui.label(format!("{}: {:.3}", label, current));
+
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(260.0, 40.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::from_gray(20));
+    if values.len() < 2 {
+        return;
+    }
+
+    let (min, max) = values
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        });
+    let range = (max - min).max(f32::EPSILON);
+    let step = rect.width() / (values.len() - 1) as f32;
+    let points = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            egui::pos2(
+                rect.left() + index as f32 * step,
+                rect.bottom() - (value - min) / range * rect.height(),
+            )
+        })
+        .collect();
+    painter.add(egui::Shape::line(
+        points,
+        egui::Stroke::new(1.0, egui::Color32::LIGHT_GREEN),
+    ));
+}
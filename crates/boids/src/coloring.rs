@@ -0,0 +1,160 @@
+//! boid colours picked from the simulation every frame, and the legend
+//! explaining them. Flocks are only worked out once per physics tick.
+
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_inspector_egui::egui;
+
+use crate::{
+    analytics::flock_labels,
+    components::{Boid, Collider, Velocity},
+    resources::{BoidAssets, BoidUniverse, ColorMode, TINT_COUNT},
+    spatial::SpatialIndex,
+    species::Species,
+};
+
+/// hue of the low end of the speed and nearby gradients, blue
+const COLD_HUE: f32 = 2.0 / 3.0;
+
+/// neighbours at which a boid shows as fully crowded
+const NEARBY_SCALE: f32 = 20.0;
+
+/// spreads consecutive flock labels far apart on the hue wheel
+const GOLDEN_RATIO: f32 = 0.618_034;
+
+/// flock label per boid as of the last physics tick
+#[derive(Resource, Default)]
+pub struct FlockLabels(HashMap<Entity, usize>);
+
+pub fn label_flocks(
+    boids: Query<(Entity, &Transform, &Collider), With<Boid>>,
+    universe: Res<BoidUniverse>,
+    boid_index: Res<SpatialIndex<Boid>>,
+    mut labels: ResMut<FlockLabels>,
+) {
+    let linked: Vec<_> = boids.iter().collect();
+    labels.0 = linked
+        .iter()
+        .map(|(entity, ..)| *entity)
+        .zip(flock_labels(&universe, &boid_index, &linked))
+        .collect();
+}
+
+type ColoredBoids<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Velocity,
+        &'static Collider,
+        &'static Species,
+        &'static mut Handle<ColorMaterial>,
+    ),
+    With<Boid>,
+>;
+
+pub fn color_boids(
+    mut boids: ColoredBoids,
+    universe: Res<BoidUniverse>,
+    flocks: Res<FlockLabels>,
+    boid_assets: Res<BoidAssets>,
+) {
+    boids
+        .iter_mut()
+        .for_each(|(entity, velocity, collider, species, mut material)| {
+            let hue = match universe.color_mode {
+                ColorMode::Species => None,
+                ColorMode::Speed => {
+                    let max_speed = universe.params(*species).max_speed.max(f32::EPSILON);
+                    Some(gradient(velocity.value.length() / max_speed))
+                }
+                ColorMode::Heading => Some(velocity.value.y.atan2(velocity.value.x) / TAU),
+                ColorMode::Nearby => Some(gradient(collider.nearby as f32 / NEARBY_SCALE)),
+                ColorMode::Flock => flocks
+                    .0
+                    .get(&entity)
+                    .map(|label| (*label as f32 * GOLDEN_RATIO).fract()),
+            };
+
+            let color = match hue {
+                Some(hue) => boid_assets.tint(hue),
+                None => boid_assets.species(*species),
+            };
+            // only touch the handle on a change, it re-batches the boid
+            if *material != color {
+                *material = color;
+            }
+        });
+}
+
+/// blue at 0 through green to red at 1
+fn gradient(t: f32) -> f32 {
+    (1.0 - t.clamp(0.0, 1.0)) * COLD_HUE
+}
+
+pub fn color_legend(ui: &mut egui::Ui, universe: &BoidUniverse) {
+    match universe.color_mode {
+        ColorMode::Species => {
+            ui.horizontal(|ui| {
+                Species::ALL.iter().for_each(|species| {
+                    ui.colored_label(egui_color(universe.params(*species).color), species.name());
+                });
+            });
+        }
+        ColorMode::Speed => {
+            ui.horizontal(|ui| {
+                ui.label("still");
+                hue_bar(ui, COLD_HUE, 0.0, 120.0);
+                ui.label("max speed");
+            });
+        }
+        ColorMode::Heading => {
+            ui.horizontal(|ui| {
+                ["E", "N", "W", "S"]
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, label)| {
+                        let from = index as f32 / 4.0;
+                        ui.label(*label);
+                        hue_bar(ui, from, from + 0.25, 30.0);
+                    });
+                ui.label("E");
+            });
+        }
+        ColorMode::Nearby => {
+            ui.horizontal(|ui| {
+                ui.label("alone");
+                hue_bar(ui, COLD_HUE, 0.0, 120.0);
+                ui.label(format!("{} seen", NEARBY_SCALE));
+            });
+        }
+        ColorMode::Flock => {
+            ui.label("one colour per flock, linked within the cohesion radius");
+        }
+    }
+}
+
+/// the tint colours from hue `from` to hue `to`
+fn hue_bar(ui: &mut egui::Ui, from: f32, to: f32, width: f32) {
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, 12.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    let step = rect.width() / TINT_COUNT as f32;
+    (0..TINT_COUNT).for_each(|index| {
+        let hue = from + (to - from) * index as f32 / (TINT_COUNT - 1) as f32;
+        let left = rect.left() + index as f32 * step;
+        painter.rect_filled(
+            egui::Rect::from_min_max(
+                egui::pos2(left, rect.top()),
+                egui::pos2(left + step, rect.bottom()),
+            ),
+            0.0,
+            egui_color(Color::hsl(hue * 360.0, 1.0, 0.5)),
+        );
+    });
+}
+
+fn egui_color(color: Color) -> egui::Color32 {
+    let [r, g, b, a] = color.as_rgba_u8();
+    egui::Color32::from_rgba_unmultiplied(r, g, b, a)
+}
@@ -0,0 +1,232 @@
+use bevy::{ecs::query::QueryItem, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{spatial::Indexed, species::Species, MAX_TRAIL_LEN};
+
+use quadtree::{aggregate::Aggregate, coord::Coord, region::Region};
+
+#[derive(Component, Debug)]
+pub struct Velocity {
+    pub value: Vec3,
+}
+
+/// steering force of the current tick, integrated and reset by `move_system`
+#[derive(Component, Debug, Default)]
+pub struct Acceleration {
+    pub value: Vec3,
+}
+
+// quad render tag
+#[derive(Component)]
+pub struct QuadNodeRect;
+
+#[derive(Component, Debug)]
+pub struct Boid;
+
+/// boids keep what steering reads of their neighbours in the index, and
+/// the flock aggregates on top
+impl Indexed for Boid {
+    type Value = Body;
+    type Aggregate = FlockAggregate;
+    type Data = (&'static Species, &'static Velocity);
+
+    fn value(
+        entity: Entity,
+        transform: &Transform,
+        (species, velocity): QueryItem<'_, Self::Data>,
+    ) -> Body {
+        Body {
+            entity,
+            species: *species,
+            position: transform.translation,
+            velocity: velocity.value,
+        }
+    }
+}
+
+#[derive(Component, Debug)]
+pub struct Cursor;
+
+#[derive(Component, Debug)]
+pub struct Collider {
+    pub radius: f32,
+    pub nearby: usize,
+}
+
+impl Collider {
+    pub fn new(radius: f32) -> Self {
+        Self { radius, nearby: 0 }
+    }
+    pub fn into_region(&self, origin: Vec3) -> Region {
+        let min =
+            Coord::from_f32(origin.x, origin.y) - Coord::from_f32(self.radius, self.radius) / 2;
+        let max =
+            Coord::from_f32(origin.x, origin.y) + Coord::from_f32(self.radius, self.radius) / 2;
+
+        Region::new(min, max)
+    }
+}
+
+/// static shape boids steer around, centered on its `Transform`.
+/// Segments run from `center - half_extent` to `center + half_extent`
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Obstacle {
+    Circle { radius: f32 },
+    Rect { size: Vec2 },
+    Segment { half_extent: Vec2 },
+}
+
+impl Indexed for Obstacle {
+    type Value = Entity;
+    type Aggregate = ();
+    type Data = ();
+
+    fn value(entity: Entity, _transform: &Transform, _data: ()) -> Entity {
+        entity
+    }
+}
+
+impl Obstacle {
+    pub const CIRCLE: Obstacle = Obstacle::Circle { radius: 30.0 };
+    pub const RECT: Obstacle = Obstacle::Rect {
+        size: Vec2::new(80.0, 40.0),
+    };
+    pub const SEGMENT: Obstacle = Obstacle::Segment {
+        half_extent: Vec2::new(60.0, 0.0),
+    };
+
+    /// side length of the square around the center that covers the whole shape
+    pub fn bounding_size(&self) -> f32 {
+        match self {
+            Obstacle::Circle { radius } => radius * 2.0,
+            Obstacle::Rect { size } => size.max_element(),
+            Obstacle::Segment { half_extent } => half_extent.abs().max_element() * 2.0,
+        }
+    }
+
+    pub fn closest_point(&self, center: Vec2, point: Vec2) -> Vec2 {
+        match self {
+            Obstacle::Circle { radius } => {
+                let offset = point - center;
+                if offset.length() <= *radius {
+                    point
+                } else {
+                    center + offset.normalize() * *radius
+                }
+            }
+            Obstacle::Rect { size } => point.clamp(center - *size / 2.0, center + *size / 2.0),
+            Obstacle::Segment { half_extent } => {
+                let start = center - *half_extent;
+                let line = *half_extent * 2.0;
+                let t = ((point - start).dot(line) / line.length_squared()).clamp(0.0, 1.0);
+                start + line * t
+            }
+        }
+    }
+}
+
+/// recent positions in a fixed ring, so a trail never grows however long
+/// its boid lives
+#[derive(Component, Debug)]
+pub struct Trail {
+    points: [Vec2; MAX_TRAIL_LEN],
+    /// where the next position goes
+    head: usize,
+    len: usize,
+}
+
+impl Default for Trail {
+    fn default() -> Self {
+        Self {
+            points: [Vec2::ZERO; MAX_TRAIL_LEN],
+            head: 0,
+            len: 0,
+        }
+    }
+}
+
+impl Trail {
+    pub fn push(&mut self, point: Vec2) {
+        self.points[self.head] = point;
+        self.head = (self.head + 1) % MAX_TRAIL_LEN;
+        self.len = (self.len + 1).min(MAX_TRAIL_LEN);
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn last(&self) -> Option<Vec2> {
+        self.recent(1).next()
+    }
+
+    /// newest first, at most `count` positions
+    pub fn recent(&self, count: usize) -> impl Iterator<Item = Vec2> + '_ {
+        (1..=self.len.min(count))
+            .map(move |age| self.points[(self.head + MAX_TRAIL_LEN - age) % MAX_TRAIL_LEN])
+    }
+}
+
+/// what a boid is able to see, overrides `BoidUniverse::perception` when attached
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Perception {
+    /// full width of the vision cone in degrees
+    pub vision_angle: f32,
+    /// width of the cone right behind the boid that is never seen, in degrees
+    pub blind_spot: f32,
+}
+
+impl Perception {
+    pub fn sees(&self, heading: Vec3, offset: Vec3) -> bool {
+        if heading.length_squared() == 0.0 || offset.length_squared() == 0.0 {
+            return true;
+        }
+        let angle = heading.angle_between(offset).to_degrees();
+        angle <= self.vision_angle / 2.0 && angle <= 180.0 - self.blind_spot / 2.0
+    }
+}
+
+#[derive(Debug)]
+pub struct Body {
+    pub entity : Entity,
+    pub species: Species,
+    pub position: Vec3,
+    pub velocity: Vec3,
+}
+
+/// per quad node summary of the boids inside, distant nodes pull as a
+/// whole in `long_range_attraction`. Neighbours steer boid by boid, as
+/// vision and species rules filter them.
+#[derive(Debug, Default, Clone)]
+pub struct FlockAggregate {
+    pub count: usize,
+    pub position_sum: Vec3,
+}
+
+impl FlockAggregate {
+    pub fn centroid(&self) -> Option<Vec3> {
+        (self.count > 0).then(|| self.position_sum / self.count as f32)
+    }
+}
+
+impl Aggregate<Body> for FlockAggregate {
+    fn add(&mut self, body: &Body) {
+        self.count += 1;
+        self.position_sum += body.position;
+    }
+
+    fn remove(&mut self, body: &Body) {
+        self.count -= 1;
+        self.position_sum -= body.position;
+        // boids come and go every tick, an empty node drops the rounding
+        // errors that piled up
+        if self.count == 0 {
+            *self = Self::default();
+        }
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.position_sum += other.position_sum;
+    }
+}
@@ -0,0 +1,287 @@
+//! boids living off food: energy drains with speed, full boids split in two
+//! with mutated genes and empty ones die
+
+use std::{collections::VecDeque, f32::consts::PI};
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_inspector_egui::{bevy_egui::EguiContexts, egui};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analytics::{plot, HISTORY_LEN},
+    components::{Boid, Velocity},
+    headless::spawn_headless_boid,
+    resources::{BoidAssets, BoidUniverse, SimRng},
+    species::{Species, SpeciesParams},
+    systems::{spawn_boid, CaughtPrey},
+};
+
+/// side length of the square patches food grows on
+const FOOD_CELL: f32 = 40.0;
+
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Energy(pub f32);
+
+/// inherited multipliers on the species params, all 1 for boids nobody bred
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    pub separation: f32,
+    pub cohesion: f32,
+    pub alignment: f32,
+    pub max_speed: f32,
+}
+
+impl Default for Genome {
+    fn default() -> Self {
+        Self {
+            separation: 1.0,
+            cohesion: 1.0,
+            alignment: 1.0,
+            max_speed: 1.0,
+        }
+    }
+}
+
+impl Genome {
+    /// the species params as this boid has them
+    pub fn express(&self, params: &SpeciesParams) -> SpeciesParams {
+        SpeciesParams {
+            separation: params.separation * self.separation,
+            cohesion: params.cohesion * self.cohesion,
+            alignment: params.alignment * self.alignment,
+            max_speed: params.max_speed * self.max_speed,
+            ..params.clone()
+        }
+    }
+
+    /// every gene scaled by up to `amount` either way
+    pub fn mutate(&self, rng: &mut impl Rng, amount: f32) -> Self {
+        let mut gene = |value: f32| (value * (1.0 + rng.gen_range(-amount..=amount))).max(0.0);
+        Self {
+            separation: gene(self.separation),
+            cohesion: gene(self.cohesion),
+            alignment: gene(self.alignment),
+            max_speed: gene(self.max_speed),
+        }
+    }
+}
+
+/// the population after a tick
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Census {
+    pub prey: usize,
+    pub predators: usize,
+    pub mean_energy: f32,
+    /// mean `Genome::max_speed`
+    pub mean_speed_gene: f32,
+}
+
+#[derive(Resource)]
+pub struct Ecology {
+    pub enabled: bool,
+    /// energy a boid starts with
+    pub birth_energy: f32,
+    /// energy at which a boid splits in two
+    pub reproduce_at: f32,
+    /// energy lost per second whatever the speed
+    pub idle_cost: f32,
+    /// energy lost per second for every 100 units of speed
+    pub speed_cost: f32,
+    /// food a boid eats per second at most
+    pub eat_rate: f32,
+    /// food a patch holds when fully grown
+    pub food_capacity: f32,
+    /// food a patch grows back per second
+    pub food_regrowth: f32,
+    /// largest relative change of a gene from parent to child
+    pub mutation: f32,
+    pub history: VecDeque<Census>,
+    /// food per patch, row by row from the bottom left corner
+    food: Vec<f32>,
+    columns: usize,
+}
+
+impl Default for Ecology {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            birth_energy: 50.0,
+            reproduce_at: 100.0,
+            idle_cost: 1.0,
+            speed_cost: 2.0,
+            eat_rate: 30.0,
+            food_capacity: 20.0,
+            food_regrowth: 2.0,
+            mutation: 0.1,
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            food: Vec::new(),
+            columns: 0,
+        }
+    }
+}
+
+impl Ecology {
+    /// one fully grown patch per cell of the world, kept while the world
+    /// keeps its size
+    pub(crate) fn fit(&mut self, size: Vec2) {
+        let columns = (size.x / FOOD_CELL).ceil().max(1.0) as usize;
+        let rows = (size.y / FOOD_CELL).ceil().max(1.0) as usize;
+        if self.columns != columns || self.food.len() != columns * rows {
+            self.columns = columns;
+            self.food = vec![self.food_capacity; columns * rows];
+        }
+    }
+
+    pub(crate) fn patch(&self, offset: Vec2) -> usize {
+        let rows = self.food.len() / self.columns;
+        let column = ((offset.x / FOOD_CELL) as usize).min(self.columns - 1);
+        let row = ((offset.y / FOOD_CELL) as usize).min(rows - 1);
+        row * self.columns + column
+    }
+
+    fn push(&mut self, census: Census) {
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(census);
+    }
+}
+
+type LivingBoids<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Velocity,
+        &'static Species,
+        Option<&'static mut Energy>,
+        Option<&'static Genome>,
+    ),
+    With<Boid>,
+>;
+
+/// the world around the boids during an ecology tick
+#[derive(SystemParam)]
+pub struct Habitat<'w> {
+    universe: Res<'w, BoidUniverse>,
+    time: Res<'w, Time>,
+    caught: Res<'w, CaughtPrey>,
+}
+
+/// one boid after the other so the draws from `SimRng` happen in entity
+/// order, boids without energy yet start with `Ecology::birth_energy`.
+/// Prey caught this tick is already gone and skipped.
+pub fn ecology_tick(
+    mut commands: Commands,
+    mut boids: LivingBoids,
+    mut ecology: ResMut<Ecology>,
+    habitat: Habitat,
+    mut rng: ResMut<SimRng>,
+    boid_assets: Option<Res<BoidAssets>>,
+) {
+    let delta = habitat.time.delta_seconds() * habitat.universe.speed;
+    let (min_x, min_y, max_x, max_y) = habitat.universe.bounds.into_f32();
+    let min = Vec2::new(min_x, min_y);
+    ecology.fit(Vec2::new(max_x, max_y) - min);
+
+    let ecology = &mut *ecology;
+    let (capacity, regrowth) = (ecology.food_capacity, ecology.food_regrowth);
+    ecology
+        .food
+        .iter_mut()
+        .for_each(|food| *food = (*food + regrowth * delta).min(capacity));
+
+    let mut census = Census::default();
+    let mut genes = 0.0;
+    for (entity, transform, velocity, species, energy, genome) in boids.iter_mut() {
+        if habitat.caught.contains(entity) {
+            continue;
+        }
+        let Some(mut energy) = energy else {
+            commands
+                .entity(entity)
+                .insert((Energy(ecology.birth_energy), Genome::default()));
+            continue;
+        };
+
+        let speed = velocity.value.length();
+        energy.0 -= (ecology.idle_cost + ecology.speed_cost * speed / 100.0) * delta;
+
+        let position = transform.translation.truncate();
+        let patch = ecology.patch(position - min);
+        let eaten = ecology.food[patch].min(ecology.eat_rate * delta);
+        ecology.food[patch] -= eaten;
+        energy.0 += eaten;
+
+        if energy.0 <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let genome = genome.copied().unwrap_or_default();
+        if energy.0 >= ecology.reproduce_at {
+            energy.0 /= 2.0;
+            let child = genome.mutate(&mut **rng, ecology.mutation);
+            let turn = rng.gen_range(-PI / 4.0..PI / 4.0);
+            let child_velocity = Quat::from_rotation_z(turn) * velocity.value;
+            let entity = match &boid_assets {
+                Some(boid_assets) => spawn_boid(
+                    &mut commands,
+                    boid_assets,
+                    *species,
+                    transform.translation,
+                    child_velocity,
+                ),
+                None => spawn_headless_boid(
+                    &mut commands,
+                    *species,
+                    transform.translation,
+                    child_velocity,
+                ),
+            };
+            commands.entity(entity).insert((Energy(energy.0), child));
+        }
+
+        match species {
+            Species::Prey => census.prey += 1,
+            Species::Predator => census.predators += 1,
+        }
+        census.mean_energy += energy.0;
+        genes += genome.max_speed;
+    }
+
+    let alive = (census.prey + census.predators).max(1) as f32;
+    census.mean_energy /= alive;
+    census.mean_speed_gene = genes / alive;
+    ecology.push(census);
+}
+
+pub fn ecology_window(mut context: EguiContexts, mut ecology: ResMut<Ecology>) {
+    egui::Window::new("Ecology")
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::Vec2::new(0.0, 0.0))
+        .default_open(false)
+        .show(context.ctx_mut(), |ui| {
+            ui.checkbox(&mut ecology.enabled, "boids eat, breed and die");
+            ui.add(egui::Slider::new(&mut ecology.birth_energy, 1.0..=200.0).text("birth energy"));
+            ui.add(egui::Slider::new(&mut ecology.reproduce_at, 1.0..=400.0).text("reproduce at"));
+            ui.add(egui::Slider::new(&mut ecology.idle_cost, 0.0..=10.0).text("idle cost"));
+            ui.add(egui::Slider::new(&mut ecology.speed_cost, 0.0..=10.0).text("speed cost"));
+            ui.add(egui::Slider::new(&mut ecology.eat_rate, 0.0..=100.0).text("eat rate"));
+            ui.add(egui::Slider::new(&mut ecology.food_capacity, 0.0..=100.0).text("food"));
+            ui.add(egui::Slider::new(&mut ecology.food_regrowth, 0.0..=20.0).text("regrowth"));
+            ui.add(egui::Slider::new(&mut ecology.mutation, 0.0..=0.5).text("mutation"));
+
+            let series = |value: fn(&Census) -> f32| -> Vec<f32> {
+                ecology.history.iter().map(value).collect()
+            };
+            plot(ui, "prey", &series(|c| c.prey as f32));
+            plot(ui, "predators", &series(|c| c.predators as f32));
+            plot(ui, "mean energy", &series(|c| c.mean_energy));
+            plot(ui, "speed gene", &series(|c| c.mean_speed_gene));
+            if ui.button("clear").clicked() {
+                ecology.history.clear();
+            }
+        });
+}
@@ -0,0 +1,290 @@
+//! an external force field boids drift in: wind, curl noise or a grid of
+//! vectors loaded from a file
+
+use bevy::prelude::*;
+use bevy_inspector_egui::{bevy_egui::EguiContexts, egui};
+
+use crate::resources::BoidUniverse;
+
+/// distance between the arrows of the overlay
+const ARROW_SPACING: f32 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlowMode {
+    Off,
+    /// the same push everywhere
+    Wind,
+    /// swirls without sources or sinks, from the curl of a noise field
+    Curl,
+    /// vectors loaded from a file, stretched over the world
+    Grid,
+}
+
+impl FlowMode {
+    pub const ALL: [FlowMode; 4] = [
+        FlowMode::Off,
+        FlowMode::Wind,
+        FlowMode::Curl,
+        FlowMode::Grid,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlowMode::Off => "off",
+            FlowMode::Wind => "wind",
+            FlowMode::Curl => "curl noise",
+            FlowMode::Grid => "grid",
+        }
+    }
+}
+
+/// vectors on a grid covering the world, the first row is the top edge
+#[derive(Debug, Clone)]
+pub struct FlowGrid {
+    pub columns: usize,
+    pub rows: usize,
+    /// row by row, each component -1 to 1
+    pub vectors: Vec<Vec2>,
+}
+
+impl FlowGrid {
+    /// a `.csv` with `dx,dy,dx,dy,...` per row, or an image where red and
+    /// green map 0 to 255 onto -1 to 1
+    pub fn load(path: &str) -> Result<Self, String> {
+        match path.ends_with(".csv") {
+            true => {
+                let text = std::fs::read_to_string(path)
+                    .map_err(|err| format!("can't read {}: {}", path, err))?;
+                Self::from_csv(&text).map_err(|err| format!("{}: {}", path, err))
+            }
+            false => Self::from_image(path),
+        }
+    }
+
+    pub fn from_csv(text: &str) -> Result<Self, String> {
+        let rows = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let values = line
+                    .split(',')
+                    .map(|value| value.trim().parse::<f32>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("line {}: expected numbers", index + 1))?;
+                if values.is_empty() || values.len() % 2 != 0 {
+                    return Err(format!("line {}: expected dx,dy pairs", index + 1));
+                }
+                Ok(values
+                    .chunks(2)
+                    .map(|pair| Vec2::new(pair[0], pair[1]))
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let columns = rows.first().map(Vec::len).unwrap_or(0);
+        if columns == 0 {
+            return Err("no vectors".to_string());
+        }
+        if rows.iter().any(|row| row.len() != columns) {
+            return Err("rows differ in length".to_string());
+        }
+
+        Ok(Self {
+            columns,
+            rows: rows.len(),
+            vectors: rows.concat(),
+        })
+    }
+
+    pub fn from_image(path: &str) -> Result<Self, String> {
+        let image = image::open(path)
+            .map_err(|err| format!("can't read {}: {}", path, err))?
+            .to_rgb8();
+        let component = |value: u8| value as f32 / 255.0 * 2.0 - 1.0;
+
+        Ok(Self {
+            columns: image.width() as usize,
+            rows: image.height() as usize,
+            vectors: image
+                .pixels()
+                .map(|pixel| Vec2::new(component(pixel[0]), component(pixel[1])))
+                .collect(),
+        })
+    }
+
+    /// bilinear, `uv` runs from 0 at the bottom left to 1 at the top right
+    pub fn sample(&self, uv: Vec2) -> Vec2 {
+        let x = uv.x.clamp(0.0, 1.0) * (self.columns - 1) as f32;
+        let y = (1.0 - uv.y.clamp(0.0, 1.0)) * (self.rows - 1) as f32;
+        let (column, row) = (x.floor() as usize, y.floor() as usize);
+        let (next_column, next_row) = (
+            (column + 1).min(self.columns - 1),
+            (row + 1).min(self.rows - 1),
+        );
+        let at = |column: usize, row: usize| self.vectors[row * self.columns + column];
+
+        let top = at(column, row).lerp(at(next_column, row), x.fract());
+        let bottom = at(column, next_row).lerp(at(next_column, next_row), x.fract());
+        top.lerp(bottom, y.fract())
+    }
+}
+
+#[derive(Resource)]
+pub struct FlowField {
+    pub mode: FlowMode,
+    /// acceleration where the field is strongest
+    pub strength: f32,
+    /// degrees, 0 blows east
+    pub wind_angle: f32,
+    /// rough size of the curl noise swirls in world units
+    pub noise_scale: f32,
+    pub seed: u32,
+    pub grid: Option<FlowGrid>,
+    pub path: String,
+    pub status: Option<String>,
+    pub show: bool,
+}
+
+impl Default for FlowField {
+    fn default() -> Self {
+        Self {
+            mode: FlowMode::Off,
+            strength: 100.0,
+            wind_angle: 0.0,
+            noise_scale: 200.0,
+            seed: 0,
+            grid: None,
+            path: "flow.png".to_string(),
+            status: None,
+            show: false,
+        }
+    }
+}
+
+impl FlowField {
+    /// the force at `position` in a world spanning `min` to `max`
+    pub fn sample(&self, position: Vec2, min: Vec2, max: Vec2) -> Vec2 {
+        let direction = match self.mode {
+            FlowMode::Off => Vec2::ZERO,
+            FlowMode::Wind => Vec2::from_angle(self.wind_angle.to_radians()),
+            FlowMode::Curl => curl(position / self.noise_scale.max(1.0), self.seed),
+            FlowMode::Grid => match &self.grid {
+                Some(grid) => grid.sample((position - min) / (max - min).max(Vec2::ONE)),
+                None => Vec2::ZERO,
+            },
+        };
+        direction * self.strength
+    }
+}
+
+/// rotated gradient of value noise, at most length 1
+fn curl(position: Vec2, seed: u32) -> Vec2 {
+    const STEP: f32 = 0.01;
+    let dx = (value_noise(position + Vec2::X * STEP, seed)
+        - value_noise(position - Vec2::X * STEP, seed))
+        / (2.0 * STEP);
+    let dy = (value_noise(position + Vec2::Y * STEP, seed)
+        - value_noise(position - Vec2::Y * STEP, seed))
+        / (2.0 * STEP);
+    (Vec2::new(dy, -dx) * 0.5).clamp_length_max(1.0)
+}
+
+/// smoothly interpolated random values on the integer lattice, 0 to 1
+fn value_noise(position: Vec2, seed: u32) -> f32 {
+    let cell = position.floor();
+    let t = position - cell;
+    let t = t * t * (Vec2::splat(3.0) - 2.0 * t);
+    let (x, y) = (cell.x as i32, cell.y as i32);
+
+    let bottom = lerp(lattice(x, y, seed), lattice(x + 1, y, seed), t.x);
+    let top = lerp(lattice(x, y + 1, seed), lattice(x + 1, y + 1, seed), t.x);
+    lerp(bottom, top, t.y)
+}
+
+fn lattice(x: i32, y: i32, seed: u32) -> f32 {
+    let mut hash = (x as u32).wrapping_mul(0x8da6_b343)
+        ^ (y as u32).wrapping_mul(0xd816_3841)
+        ^ seed.wrapping_mul(0xcb1a_b31f);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0x5bd1_e995);
+    hash ^= hash >> 15;
+    hash as f32 / u32::MAX as f32
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+pub fn flow_controls(mut context: EguiContexts, mut flow: ResMut<FlowField>) {
+    egui::Window::new("Flow")
+        .anchor(egui::Align2::CENTER_TOP, egui::Vec2::new(0.0, 0.0))
+        .default_open(false)
+        .show(context.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                FlowMode::ALL.iter().for_each(|mode| {
+                    ui.radio_value(&mut flow.mode, *mode, mode.name());
+                });
+            });
+            ui.add(egui::Slider::new(&mut flow.strength, 0.0..=1000.0).text("strength"));
+            match flow.mode {
+                FlowMode::Wind => {
+                    ui.add(egui::Slider::new(&mut flow.wind_angle, 0.0..=360.0).text("direction"));
+                }
+                FlowMode::Curl => {
+                    ui.add(egui::Slider::new(&mut flow.noise_scale, 20.0..=1000.0).text("scale"));
+                    ui.add(egui::Slider::new(&mut flow.seed, 0..=100).text("seed"));
+                }
+                FlowMode::Grid => {
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(&mut flow.path);
+                        if ui.button("load").clicked() {
+                            match FlowGrid::load(&flow.path) {
+                                Ok(grid) => {
+                                    flow.status =
+                                        Some(format!("{}x{} vectors", grid.columns, grid.rows));
+                                    flow.grid = Some(grid);
+                                }
+                                Err(err) => flow.status = Some(err),
+                            }
+                        }
+                    });
+                    if let Some(status) = &flow.status {
+                        ui.label(status);
+                    }
+                }
+                FlowMode::Off => {}
+            }
+            ui.checkbox(&mut flow.show, "Render Flow");
+        });
+}
+
+/// an arrow per grid point, nearly reaching the next one where the field
+/// is at full strength
+pub fn render_flow(universe: Res<BoidUniverse>, flow: Res<FlowField>, mut gizmos: Gizmos) {
+    if !flow.show || flow.mode == FlowMode::Off {
+        return;
+    }
+
+    let (min_x, min_y, max_x, max_y) = universe.bounds.into_f32();
+    let (min, max) = (Vec2::new(min_x, min_y), Vec2::new(max_x, max_y));
+    let color = Color::rgba(0.3, 0.8, 1.0, 0.6);
+    let columns = ((max.x - min.x) / ARROW_SPACING) as usize;
+    let rows = ((max.y - min.y) / ARROW_SPACING) as usize;
+
+    (0..=rows).for_each(|row| {
+        (0..=columns).for_each(|column| {
+            let start = min + Vec2::new(column as f32, row as f32) * ARROW_SPACING;
+            let force = flow.sample(start, min, max) / flow.strength.max(f32::EPSILON);
+            if force.length_squared() < 0.0001 {
+                return;
+            }
+
+            let end = start + force * ARROW_SPACING * 0.8;
+            let back = (start - end).normalize() * ARROW_SPACING * 0.2;
+            gizmos.line_2d(start, end, color);
+            gizmos.line_2d(end, end + Vec2::from_angle(0.5).rotate(back), color);
+            gizmos.line_2d(end, end + Vec2::from_angle(-0.5).rotate(back), color);
+        });
+    });
+}
@@ -0,0 +1,249 @@
+//! the simulation without a window or a gpu, for experiments and CI
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+
+use crate::{
+    analytics::{measure, FlockStats},
+    components::{Acceleration, Boid, Collider, Obstacle, Velocity},
+    ecology::Ecology,
+    init::{insert_world, random_boid},
+    resources::{BoidUniverse, QuadBench, SimRng, SimTick},
+    snapshot::{Snapshot, SnapshotBoid},
+    spatial::SpatialIndex,
+    species::Species,
+    BoidSimulationPlugin, BOID_SIZE, DEFAULT_SEED, PHYISCS_TICK_RATE,
+};
+
+#[derive(Resource, Debug, Clone)]
+pub struct HeadlessConfig {
+    pub boids: usize,
+    pub ticks: u64,
+    pub seed: u64,
+    pub world_size: Vec2,
+    /// overrides by `BoidUniverse` param name, like `prey.cohesion`
+    pub params: Vec<(String, f32)>,
+    /// start from this instead of `boids` random ones in `world_size`
+    pub scene: Option<Snapshot>,
+    /// where to save a snapshot after the last tick
+    pub save: Option<String>,
+    /// boids eat, breed and die, see `Ecology`
+    pub ecology: bool,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        Self {
+            boids: 1000,
+            ticks: 900,
+            seed: DEFAULT_SEED,
+            world_size: Vec2::new(1280.0, 720.0),
+            params: Vec::new(),
+            scene: None,
+            save: None,
+            ecology: false,
+        }
+    }
+}
+
+/// timing and flock metrics after the last tick
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub ticks: u64,
+    pub elapsed: Duration,
+    pub boids: usize,
+    pub avarage_query_time: u128,
+    pub avarage_build_time: u128,
+    pub avarage_long_range_time: u128,
+    pub mean_speed: f32,
+    pub stats: FlockStats,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.elapsed.as_secs_f64();
+        writeln!(f, "ticks: {}", self.ticks)?;
+        writeln!(f, "elapsed: {:.3} s", seconds)?;
+        writeln!(
+            f,
+            "ticks per second: {:.1}",
+            self.ticks as f64 / seconds.max(f64::EPSILON)
+        )?;
+        writeln!(f, "boids: {}", self.boids)?;
+        writeln!(f, "avg. query time: {} ns", self.avarage_query_time)?;
+        writeln!(f, "avg. build time: {} us", self.avarage_build_time)?;
+        writeln!(
+            f,
+            "avg. long range time: {} ns",
+            self.avarage_long_range_time
+        )?;
+        writeln!(f, "mean speed: {:.2}", self.mean_speed)?;
+        writeln!(f, "polarization: {:.4}", self.stats.polarization)?;
+        writeln!(f, "angular momentum: {:.4}", self.stats.angular_momentum)?;
+        writeln!(f, "mean nearest: {:.2}", self.stats.mean_nearest)?;
+        writeln!(f, "min nearest: {:.2}", self.stats.min_nearest)?;
+        writeln!(f, "mean nearby: {:.2}", self.stats.mean_nearby)?;
+        write!(f, "flocks: {}", self.stats.flocks)
+    }
+}
+
+/// runs `config.ticks` physics ticks as fast as possible
+pub fn run(config: &HeadlessConfig) -> Result<Report, String> {
+    if let Some((name, _)) = config
+        .params
+        .iter()
+        .find(|(name, _)| !BoidUniverse::PARAM_NAMES.contains(&name.as_str()))
+    {
+        return Err(format!("unknown param `{}`", name));
+    }
+    // random boids are placed with `%` by half the size
+    if config.scene.is_none()
+        && (!config.world_size.is_finite() || config.world_size.min_element() < 2.0)
+    {
+        return Err(format!(
+            "world size must be at least 2x2, got {}x{}",
+            config.world_size.x, config.world_size.y
+        ));
+    }
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(SimRng::new(config.seed));
+    app.insert_resource(config.clone());
+    app.add_plugins(BoidSimulationPlugin);
+    app.world.resource_mut::<Ecology>().enabled = config.ecology;
+    // every update advances exactly one physics tick
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / PHYISCS_TICK_RATE as f64,
+    )));
+    app.add_systems(Startup, init_headless_scene);
+    app.finish();
+    app.cleanup();
+
+    let start = Instant::now();
+    while app.world.resource::<SimTick>().0 < config.ticks {
+        app.update();
+    }
+    let elapsed = start.elapsed();
+
+    if let Some(path) = &config.save {
+        save_snapshot(&mut app.world, path)?;
+    }
+
+    Ok(report(&mut app.world, elapsed))
+}
+
+/// `name = value` per line, `#` starts a comment
+pub fn parse_params(text: &str) -> Result<Vec<(String, f32)>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(number, line)| (number + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(number, line)| {
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected `name = value`", number))?;
+            let value = value
+                .trim()
+                .parse::<f32>()
+                .map_err(|err| format!("line {}: {}", number, err))?;
+            Ok((name.trim().to_string(), value))
+        })
+        .collect()
+}
+
+fn init_headless_scene(
+    mut commands: Commands,
+    config: Res<HeadlessConfig>,
+    mut rng: ResMut<SimRng>,
+) {
+    let size = config.world_size;
+    let mut universe = match &config.scene {
+        Some(scene) => scene.universe(),
+        None => BoidUniverse::new(size / -2.0, size / 2.0),
+    };
+    config.params.iter().for_each(|(name, value)| {
+        if let Some(param) = universe.param_mut(name) {
+            *param = *value;
+        }
+    });
+
+    if let Some(scene) = &config.scene {
+        scene.restore(&mut commands, &universe, |commands, _, boid| {
+            spawn_headless_boid(
+                commands,
+                boid.species,
+                boid.transform.translation,
+                boid.velocity,
+            )
+        });
+        commands.insert_resource(universe);
+        return;
+    }
+
+    insert_world(&mut commands, universe);
+    for _ in 0..config.boids {
+        let (position, velocity) = random_boid(&mut rng, size.x, size.y);
+        spawn_headless_boid(&mut commands, Species::Prey, position, velocity);
+    }
+}
+
+pub(crate) fn spawn_headless_boid(
+    commands: &mut Commands,
+    species: Species,
+    position: Vec3,
+    velocity: Vec3,
+) -> Entity {
+    commands
+        .spawn(TransformBundle::from_transform(
+            Transform::from_translation(position),
+        ))
+        .insert(Boid)
+        .insert(species)
+        .insert(Velocity { value: velocity })
+        .insert(Acceleration::default())
+        .insert(Collider::new(BOID_SIZE))
+        .id()
+}
+
+fn save_snapshot(world: &mut World, path: &str) -> Result<(), String> {
+    let mut boids = world.query_filtered::<SnapshotBoid, With<Boid>>();
+    let mut obstacles = world.query::<(&Transform, &Obstacle)>();
+    Snapshot::capture(
+        world.resource::<BoidUniverse>(),
+        boids.iter(world),
+        obstacles.iter(world),
+    )
+    .save(path)
+}
+
+fn report(world: &mut World, elapsed: Duration) -> Report {
+    let mut query =
+        world.query_filtered::<(Entity, &Transform, &Velocity, &Collider), With<Boid>>();
+    let tick = world.resource::<SimTick>().0;
+    let stats = measure(
+        world.resource::<BoidUniverse>(),
+        world.resource::<SpatialIndex<Boid>>(),
+        tick,
+        query.iter(world),
+    );
+    let speed = query.iter(world).fold(0.0, |speed, (_, _, velocity, _)| {
+        speed + velocity.value.length()
+    });
+    let bench = world.resource::<QuadBench>();
+
+    Report {
+        ticks: tick,
+        elapsed,
+        boids: stats.boids,
+        avarage_query_time: bench.avarage_query_time,
+        avarage_build_time: bench.avarage_build_time,
+        avarage_long_range_time: bench.avarage_long_range_time,
+        mean_speed: speed / stats.boids.max(1) as f32,
+        stats,
+    }
+}
@@ -0,0 +1,86 @@
+use super::components::{Boid, Cursor, Obstacle};
+use super::spatial::SpatialIndex;
+use super::{
+    resources::{BoidAssets, SimRng},
+    systems::spawn_boid,
+    BoidUniverse, CURSOR_QUAD_SIZE,
+};
+use crate::species::Species;
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+use quadtree::region::Region;
+use rand::Rng;
+
+pub fn init_boid_scene(
+    mut commands: Commands,
+    window: Query<&Window>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut rng: ResMut<SimRng>,
+) {
+    let window = window.single();
+
+    let universe = BoidUniverse::new(
+        Vec2::new(window.width() / -2.0, window.height() / -2.0),
+        Vec2::new(window.width() / 2.0, window.height() / 2.0),
+    );
+    let boid_assets = BoidAssets::new(&mut meshes, &mut materials, &universe);
+    insert_world(&mut commands, universe);
+
+    let _size = 5.0;
+
+    // spawn cursor visiuals
+
+    commands
+        .spawn(MaterialMesh2dBundle {
+            mesh: meshes
+                .add(Mesh::from(shape::Quad::new(Vec2::splat(CURSOR_QUAD_SIZE))))
+                .into(),
+            material: materials.add(ColorMaterial::from(Color::Rgba {
+                red: 0.0,
+                green: 0.0,
+                blue: 1.0,
+                alpha: 0.4,
+            })),
+            ..default()
+        })
+        .insert(Cursor);
+
+    // spawn default boids
+    for _ in 0..1000 {
+        let (position, velocity) = random_boid(&mut rng, window.width(), window.height());
+        spawn_boid(
+            &mut commands,
+            &boid_assets,
+            Species::Prey,
+            position,
+            velocity,
+        );
+    }
+    commands.insert_resource(boid_assets);
+}
+
+/// the universe and the boid and obstacle indexes, covering the same world
+pub fn insert_world(commands: &mut Commands, universe: BoidUniverse) {
+    insert_indexes(commands, &universe.bounds);
+    commands.insert_resource(universe);
+}
+
+/// empty boid and obstacle indexes covering `bounds`
+pub fn insert_indexes(commands: &mut Commands, bounds: &Region) {
+    commands.insert_resource(SpatialIndex::<Boid>::new(bounds.clone()));
+    commands.insert_resource(SpatialIndex::<Obstacle>::new(bounds.clone()));
+}
+
+/// position and velocity for a boid somewhere in a world centered on the origin
+pub fn random_boid(rng: &mut SimRng, width: f32, height: f32) -> (Vec3, Vec3) {
+    let x = rng.gen::<i32>() % (width / 2.0) as i32;
+    let y = rng.gen::<i32>() % (height / 2.0) as i32;
+    let initial_speed = 200.0 + rng.gen::<f32>() * 200.0;
+    let velocity = Vec3::new(
+        (rng.gen::<f32>() - 0.5) * initial_speed,
+        (rng.gen::<f32>() - 0.5) * initial_speed,
+        0.0,
+    );
+
+    (Vec3::new(x as f32, y as f32, 0.0), velocity)
+}
@@ -0,0 +1,411 @@
+use std::f32::consts::TAU;
+
+use self::analytics::AnalyticsPlugin;
+use self::coloring::{color_boids, color_legend, label_flocks, FlockLabels};
+use self::components::{Boid, Obstacle, Perception, Velocity};
+use self::ecology::{ecology_tick, ecology_window, Ecology};
+use self::flow::{flow_controls, render_flow, FlowField};
+use self::init::*;
+use self::presets::{blend_presets, preset_controls, Presets};
+use self::replay::{Replay, ReplayPlugin};
+use self::resources::*;
+use self::snapshot::{snapshot_controls, SnapshotUi};
+use self::spatial::{SpatialIndex, SpatialIndexPlugin, SpatialSync};
+use self::species::{Species, SpeciesParams, SpeciesRule};
+use self::systems::*;
+use self::trails::{record_trails, render_trails, sync_trails};
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::EguiContexts;
+use bevy_inspector_egui::egui;
+
+mod coloring;
+mod init;
+mod resources;
+mod systems;
+#[cfg(test)]
+mod tests;
+mod trails;
+
+pub const PHYISCS_TICK_RATE: f32 = 90.;
+pub const BOID_SPAWN_RATE: f32 = 100.0;
+pub const CURSOR_QUAD_SIZE: f32 = 100.0;
+pub const BOID_SIZE: f32 = 5.0;
+pub const DEFAULT_SEED: u64 = 0;
+/// positions a trail can hold, the trail length slider stops here
+pub const MAX_TRAIL_LEN: usize = 64;
+
+pub mod analytics;
+pub mod components;
+pub mod ecology;
+pub mod flow;
+pub mod headless;
+pub mod presets;
+pub mod replay;
+pub mod snapshot;
+pub mod spatial;
+pub mod species;
+
+pub use self::resources::{SimRng, SimTick};
+
+/// window, rendering and egui on top of `BoidSimulationPlugin`
+pub struct BoidPlugin;
+
+impl Plugin for BoidPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(BoidSimulationPlugin);
+        app.add_plugins(ReplayPlugin);
+        app.add_plugins(AnalyticsPlugin);
+        app.insert_resource(SelectedBoid::default());
+        app.insert_resource(SnapshotUi::default());
+        app.insert_resource(Presets::load_or_builtin());
+        app.add_systems(Startup, init_boid_scene);
+        app.add_systems(
+            Update,
+            handle_mouse.run_if(not(resource_exists::<Replay>())),
+        );
+        app.add_systems(FixedUpdate, record_trails.after(advance_tick));
+        app.init_resource::<FlockLabels>();
+        app.add_systems(
+            FixedUpdate,
+            label_flocks
+                .after(advance_tick)
+                .run_if(|universe: Res<BoidUniverse>| universe.color_mode == ColorMode::Flock),
+        );
+        app.add_systems(
+            Update,
+            (
+                ui_controls,
+                blend_presets,
+                snapshot_controls,
+                color_boids,
+                sync_trails,
+                render_trails,
+                render_quadtree,
+                render_perception,
+                render_obstacles,
+                flow_controls,
+                render_flow,
+                ecology_window,
+            ),
+        );
+    }
+}
+
+/// the simulation alone, without anything needing a window or a gpu.
+/// The scene, a `BoidUniverse` and the boid and obstacle indexes, is set
+/// up by whoever adds it.
+pub struct BoidSimulationPlugin;
+
+impl Plugin for BoidSimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(QuadBench::default());
+        app.insert_resource(SimTick::default());
+        // keeps a seed inserted before the plugin
+        app.init_resource::<SimRng>();
+        app.init_resource::<FlowField>();
+        app.init_resource::<Ecology>();
+        app.init_resource::<CaughtPrey>();
+        app.insert_resource(Time::<Fixed>::from_hz(PHYISCS_TICK_RATE as f64));
+        app.add_plugins(SpatialIndexPlugin::<Obstacle>::default());
+        // boids move every tick, their index syncs right before they steer
+        app.add_plugins(SpatialIndexPlugin::<Boid>::in_schedule(FixedUpdate));
+        app.add_systems(Update, count_boids);
+        app.add_systems(
+            FixedUpdate,
+            (update_boids, move_system, respawn_escaped, advance_tick)
+                .chain()
+                .after(SpatialSync)
+                // a replay moves the boids instead
+                .run_if(not(resource_exists::<Replay>())),
+        );
+        app.add_systems(
+            FixedUpdate,
+            ecology_tick
+                .after(respawn_escaped)
+                .before(advance_tick)
+                .run_if(|ecology: Res<Ecology>| ecology.enabled)
+                .run_if(not(resource_exists::<Replay>())),
+        );
+    }
+}
+
+fn ui_controls(
+    _commands: Commands,
+    mut context: EguiContexts,
+    mut universe: ResMut<BoidUniverse>,
+    // diagnostics: Res<Diagnostics>,
+    bench: Res<QuadBench>,
+    mut presets: ResMut<Presets>,
+) {
+    egui::Window::new("Boid Control")
+        .anchor(egui::Align2::LEFT_TOP, egui::Vec2::new(0.0, 0.0))
+        .show(context.ctx_mut(), |ui| {
+            egui::CollapsingHeader::new("presets")
+                .show(ui, |ui| preset_controls(ui, &mut presets, &universe));
+            Species::ALL.iter().for_each(|species| {
+                let params = &mut universe.species[species.index()];
+                egui::CollapsingHeader::new(species.name())
+                    .default_open(*species == Species::Prey)
+                    .show(ui, |ui| species_controls(ui, params));
+            });
+            egui::CollapsingHeader::new("rules").show(ui, |ui| rule_matrix(ui, &mut universe));
+            ui.add(egui::Slider::new(&mut universe.speed, 0.0..=10.0).text("speed"));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut universe.steering, SteeringMode::Reynolds, "reynolds");
+                ui.radio_value(&mut universe.steering, SteeringMode::Legacy, "legacy");
+            });
+            if universe.steering == SteeringMode::Reynolds {
+                ui.add(egui::Slider::new(&mut universe.min_speed, 0.0..=500.0).text("min speed"));
+                ui.add(egui::Slider::new(&mut universe.max_force, 0.0..=5000.0).text("max force"));
+                ui.add(
+                    egui::Slider::new(&mut universe.max_turn_rate, 0.0..=1080.0)
+                        .text("max turn rate"),
+                );
+                ui.add(egui::Slider::new(&mut universe.drag, 0.0..=2.0).text("drag"));
+                ui.add(
+                    egui::Slider::new(&mut universe.separation_radius, 1.0..=100.0)
+                        .text("separation radius"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut universe.cohesion_radius, 1.0..=100.0)
+                        .text("cohesion radius"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut universe.alignment_radius, 1.0..=100.0)
+                        .text("alignment radius"),
+                );
+            }
+            ui.add(
+                egui::Slider::new(&mut universe.perception.vision_angle, 0.0..=360.0)
+                    .text("vision angle"),
+            );
+            ui.add(
+                egui::Slider::new(&mut universe.perception.blind_spot, 0.0..=360.0)
+                    .text("blind spot"),
+            );
+            ui.add(egui::Slider::new(&mut universe.avoidance, 0.0..=2.0).text("avoidance"));
+            ui.add(
+                egui::Slider::new(&mut universe.avoidance_distance, 0.0..=200.0).text("look ahead"),
+            );
+            ui.horizontal(|ui| {
+                ui.label("Border:");
+                ui.radio_value(&mut universe.border, BorderMode::Bounce, "bounce");
+                ui.radio_value(&mut universe.border, BorderMode::Wrap, "wrap")
+                    .on_hover_text("boids don't see or hunt across the edge");
+                ui.radio_value(&mut universe.border, BorderMode::Steer, "steer");
+                ui.radio_value(&mut universe.border, BorderMode::Respawn, "respawn")
+                    .on_hover_text("the same boid moves, replays show it jump");
+            });
+            if matches!(universe.border, BorderMode::Bounce | BorderMode::Steer) {
+                ui.add(egui::Slider::new(&mut universe.border_margin, 0.0..=200.0).text("margin"));
+            }
+            if universe.border == BorderMode::Steer {
+                ui.add(
+                    egui::Slider::new(&mut universe.border_force, 0.0..=5.0).text("border force"),
+                );
+            }
+            ui.horizontal(|ui| {
+                ui.label("Mouse:");
+                ui.radio_value(&mut universe.mouse_tool, MouseTool::Boids, "boids");
+                ui.radio_value(&mut universe.mouse_tool, MouseTool::Obstacles, "obstacles");
+            });
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut universe.mouse_tool, MouseTool::Attract, "attract");
+                ui.radio_value(&mut universe.mouse_tool, MouseTool::Repel, "repel");
+                ui.radio_value(&mut universe.mouse_tool, MouseTool::Vortex, "vortex");
+            });
+            if universe.mouse_tool == MouseTool::Boids {
+                ui.horizontal(|ui| {
+                    Species::ALL.iter().for_each(|species| {
+                        ui.radio_value(&mut universe.spawn_species, *species, species.name());
+                    });
+                });
+            }
+            if universe.mouse_tool == MouseTool::Obstacles {
+                ui.horizontal(|ui| {
+                    ui.radio_value(&mut universe.obstacle, Obstacle::CIRCLE, "circle");
+                    ui.radio_value(&mut universe.obstacle, Obstacle::RECT, "rect");
+                    ui.radio_value(&mut universe.obstacle, Obstacle::SEGMENT, "segment");
+                });
+            }
+            if universe.mouse_tool.is_force() {
+                ui.add(
+                    egui::Slider::new(&mut universe.cursor_strength, 0.0..=3000.0).text("strength"),
+                );
+                ui.add(egui::Slider::new(&mut universe.cursor_radius, 10.0..=400.0).text("radius"));
+            }
+            ui.add(egui::Checkbox::new(
+                &mut universe.long_range,
+                "Long range attraction",
+            ));
+            ui.add(egui::Slider::new(&mut universe.long_range_theta, 0.1..=2.0).text("theta"));
+            ui.add(
+                egui::Slider::new(&mut universe.long_range_strength, 0.0..=1.0).text("long range"),
+            );
+            ui.add(egui::Checkbox::new(
+                &mut universe.show_graph,
+                "Render Graph",
+            ));
+            ui.add(egui::Checkbox::new(
+                &mut universe.show_trails,
+                "Render Trails",
+            ));
+            if universe.show_trails {
+                ui.add(
+                    egui::Slider::new(&mut universe.trail_length, 2..=MAX_TRAIL_LEN)
+                        .text("trail length"),
+                );
+                ui.add(egui::Slider::new(&mut universe.trail_decay, 0.5..=1.0).text("trail decay"));
+                ui.horizontal(|ui| {
+                    ui.label("Trails:");
+                    let subset = &mut universe.trail_subset;
+                    ui.radio_value(subset, TrailSubset::All, "all");
+                    ui.radio_value(subset, TrailSubset::Selected, "selected");
+                    ui.radio_value(subset, TrailSubset::Sample, "every 10th");
+                });
+            }
+            ui.horizontal(|ui| {
+                ui.label("Colour:");
+                ColorMode::ALL.iter().for_each(|mode| {
+                    ui.radio_value(&mut universe.color_mode, *mode, mode.name());
+                });
+            });
+            color_legend(ui, &universe);
+            ui.label(format!("Boid Count: {}", universe.boid_count));
+            // diagnostics
+            //     .iter()
+            //     .for_each(|diagnostic| match diagnostic.value() {
+            //         Some(value) => {
+            //             ui.label(format!("{} : {:.2}", diagnostic.name, value));
+            //         }
+            //         None => {}
+            //     });
+            ui.label(format!("avg. query time: {} ns", bench.avarage_query_time));
+            ui.label(format!("avg. build time: {} us", bench.avarage_build_time));
+            ui.label(format!(
+                "avg. long range time: {} ns",
+                bench.avarage_long_range_time
+            ));
+        });
+    universe.mouse_used_by_egui = context.ctx_mut().wants_pointer_input();
+}
+
+fn species_controls(ui: &mut egui::Ui, params: &mut SpeciesParams) {
+    ui.add(egui::Slider::new(&mut params.separation, 0.0..=1.0).text("speration"));
+    ui.add(egui::Slider::new(&mut params.cohesion, 0.0..=1.0).text("cohesion"));
+    ui.add(egui::Slider::new(&mut params.alignment, 0.0..=1.0).text("alignment"));
+    ui.add(egui::Slider::new(&mut params.max_speed, 0.0..=1000.0).text("max speed"));
+    ui.add(egui::Slider::new(&mut params.flee, 0.0..=2.0).text("flee"));
+    ui.add(egui::Slider::new(&mut params.chase, 0.0..=2.0).text("chase"));
+    ui.add(egui::Slider::new(&mut params.hunt_radius, 0.0..=400.0).text("hunt radius"));
+    ui.add(egui::Slider::new(&mut params.spawn_count, 1..=500).text("spawn count"));
+}
+
+/// one row per species, telling how it treats each column species
+fn rule_matrix(ui: &mut egui::Ui, universe: &mut BoidUniverse) {
+    egui::Grid::new("species_rules").show(ui, |ui| {
+        ui.label("");
+        Species::ALL.iter().for_each(|other| {
+            ui.label(other.name());
+        });
+        ui.end_row();
+
+        Species::ALL.iter().for_each(|species| {
+            ui.label(species.name());
+            Species::ALL.iter().for_each(|other| {
+                let rule = &mut universe.rules[species.index()][other.index()];
+                egui::ComboBox::from_id_source((species.index(), other.index()))
+                    .selected_text(rule.name())
+                    .show_ui(ui, |ui| {
+                        SpeciesRule::ALL.iter().for_each(|option| {
+                            ui.selectable_value(rule, *option, option.name());
+                        });
+                    });
+            });
+            ui.end_row();
+        });
+    });
+}
+
+fn render_quadtree(
+    _commands: Commands,
+    universe: ResMut<BoidUniverse>,
+    boid_index: Res<SpatialIndex<Boid>>,
+    mut gizmos : Gizmos,
+) {
+    if !universe.show_graph {
+        return;
+    }
+
+    let regions = boid_index.tree().get_regions();
+
+    regions.iter().for_each(|region| {
+        let (min_x, min_y, max_x, max_y) = region.into_f32();
+
+        let bottom_left = Vec3::new(min_x, min_y, 0.0);
+        let bottom_right = Vec3::new(max_x, min_y, 0.0);
+        let top_right = Vec3::new(max_x, max_y, 0.0);
+        let top_left = Vec3::new(min_x, max_y, 0.0);
+
+        gizmos.line(bottom_left, bottom_right, Color::WHITE);
+        gizmos.line(bottom_right, top_right, Color::WHITE);
+        gizmos.line(top_right, top_left, Color::WHITE);
+        gizmos.line(top_left, bottom_left, Color::WHITE);
+    })
+}
+
+fn render_perception(
+    universe: Res<BoidUniverse>,
+    selected: Res<SelectedBoid>,
+    boids: Query<(&Transform, &Velocity, Option<&Perception>)>,
+    mut gizmos: Gizmos,
+) {
+    let Some((transform, velocity, perception)) =
+        selected.0.and_then(|entity| boids.get(entity).ok())
+    else {
+        return;
+    };
+
+    let perception = perception.unwrap_or(&universe.perception);
+    let position = transform.translation.truncate();
+    let heading = velocity.value.x.atan2(velocity.value.y);
+    let radius = match universe.steering {
+        SteeringMode::Legacy => universe.vision * 10.0,
+        SteeringMode::Reynolds => universe.max_radius(),
+    };
+
+    // the blind spot eats into the cone from behind
+    let vision = perception
+        .vision_angle
+        .min(360.0 - perception.blind_spot)
+        .to_radians();
+    gizmos.arc_2d(position, heading, vision, radius, Color::GREEN);
+    if vision < TAU {
+        [heading - vision / 2.0, heading + vision / 2.0]
+            .iter()
+            .for_each(|angle| {
+                gizmos.line_2d(
+                    position,
+                    position + Vec2::from(angle.sin_cos()) * radius,
+                    Color::GREEN,
+                );
+            });
+    }
+    gizmos.circle_2d(position, BOID_SIZE, Color::GREEN);
+}
+
+fn render_obstacles(obstacles: Query<(&Transform, &Obstacle)>, mut gizmos: Gizmos) {
+    obstacles.iter().for_each(|(transform, obstacle)| {
+        let center = transform.translation.truncate();
+        match obstacle {
+            Obstacle::Circle { radius } => {
+                gizmos.circle_2d(center, *radius, Color::ORANGE_RED);
+            }
+            Obstacle::Rect { size } => gizmos.rect_2d(center, 0.0, *size, Color::ORANGE_RED),
+            Obstacle::Segment { half_extent } => {
+                let (start, end) = (center - *half_extent, center + *half_extent);
+                gizmos.line_2d(start, end, Color::ORANGE_RED);
+            }
+        }
+    });
+}
@@ -0,0 +1,235 @@
+//! named slider settings kept in a RON file, blended into smoothly
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::resources::BoidUniverse;
+
+pub const PRESETS_PATH: &str = "presets.ron";
+
+/// values by `BoidUniverse` param name, params left out stay as they are
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub params: BTreeMap<String, f32>,
+}
+
+impl Preset {
+    fn new(name: &str, params: &[(&str, f32)]) -> Self {
+        Self {
+            name: name.to_string(),
+            params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), *value))
+                .collect(),
+        }
+    }
+
+    /// every param as it is right now
+    pub fn capture(name: &str, universe: &BoidUniverse) -> Self {
+        Self {
+            name: name.to_string(),
+            params: BoidUniverse::PARAM_NAMES
+                .iter()
+                .filter_map(|param| Some((param.to_string(), universe.param(param)?)))
+                .collect(),
+        }
+    }
+}
+
+pub fn builtin_presets() -> Vec<Preset> {
+    vec![
+        Preset::new(
+            "tight school",
+            &[
+                ("prey.separation", 0.3),
+                ("prey.cohesion", 0.6),
+                ("prey.alignment", 0.8),
+                ("separation_radius", 8.0),
+                ("cohesion_radius", 40.0),
+                ("alignment_radius", 35.0),
+                ("max_turn_rate", 540.0),
+            ],
+        ),
+        Preset::new(
+            "murmuration",
+            &[
+                ("prey.separation", 0.2),
+                ("prey.cohesion", 0.3),
+                ("prey.alignment", 0.5),
+                ("prey.max_speed", 380.0),
+                ("cohesion_radius", 60.0),
+                ("alignment_radius", 45.0),
+                ("vision_angle", 300.0),
+                ("max_turn_rate", 720.0),
+            ],
+        ),
+        Preset::new(
+            "chaos",
+            &[
+                ("prey.separation", 1.0),
+                ("prey.cohesion", 0.0),
+                ("prey.alignment", 0.0),
+                ("prey.max_speed", 500.0),
+                ("drag", 0.0),
+                ("max_turn_rate", 1080.0),
+            ],
+        ),
+    ]
+}
+
+struct Blend {
+    from: BTreeMap<String, f32>,
+    to: BTreeMap<String, f32>,
+    elapsed: f32,
+}
+
+#[derive(Resource)]
+pub struct Presets {
+    pub presets: Vec<Preset>,
+    pub selected: usize,
+    /// name for saving the current settings
+    pub name: String,
+    /// how long loading a preset takes to blend in
+    pub blend_seconds: f32,
+    pub status: Option<String>,
+    blend: Option<Blend>,
+}
+
+impl Presets {
+    /// the presets file if there is one, the built-in ones otherwise
+    pub fn load_or_builtin() -> Self {
+        let (presets, status) = match std::fs::read_to_string(PRESETS_PATH) {
+            Ok(text) => match ron::from_str::<Vec<Preset>>(&text) {
+                Ok(presets) => (presets, None),
+                Err(err) => (
+                    builtin_presets(),
+                    Some(format!("{}: {}", PRESETS_PATH, err)),
+                ),
+            },
+            Err(_) => (builtin_presets(), None),
+        };
+
+        Self {
+            presets,
+            selected: 0,
+            name: String::new(),
+            blend_seconds: 2.0,
+            status,
+            blend: None,
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(&self.presets, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())?;
+        std::fs::write(PRESETS_PATH, text)
+            .map_err(|err| format!("can't write {}: {}", PRESETS_PATH, err))
+    }
+
+    /// starts blending from the current values towards the selected preset
+    pub fn apply(&mut self, universe: &BoidUniverse) {
+        let Some(preset) = self.presets.get(self.selected) else {
+            return;
+        };
+
+        self.blend = Some(Blend {
+            from: preset
+                .params
+                .keys()
+                .filter_map(|name| Some((name.clone(), universe.param(name)?)))
+                .collect(),
+            to: preset.params.clone(),
+            elapsed: 0.0,
+        });
+    }
+
+    /// `delta` seconds further along the blend, if one is running
+    pub fn advance(&mut self, delta: f32, universe: &mut BoidUniverse) {
+        let duration = self.blend_seconds;
+        let Some(blend) = self.blend.as_mut() else {
+            return;
+        };
+
+        blend.elapsed += delta;
+        let t = match duration > 0.0 {
+            true => (blend.elapsed / duration).min(1.0),
+            false => 1.0,
+        };
+        let eased = t * t * (3.0 - 2.0 * t);
+
+        blend.to.iter().for_each(|(name, to)| {
+            let from = blend.from.get(name).copied().unwrap_or(*to);
+            if let Some(param) = universe.param_mut(name) {
+                *param = from + (to - from) * eased;
+            }
+        });
+
+        if t >= 1.0 {
+            self.blend = None;
+        }
+    }
+
+    pub fn is_blending(&self) -> bool {
+        self.blend.is_some()
+    }
+}
+
+/// moves the params along an eased curve towards the preset being loaded
+pub fn blend_presets(
+    time: Res<Time>,
+    mut presets: ResMut<Presets>,
+    mut universe: ResMut<BoidUniverse>,
+) {
+    presets.advance(time.delta_seconds(), &mut universe);
+}
+
+pub fn preset_controls(ui: &mut egui::Ui, presets: &mut Presets, universe: &BoidUniverse) {
+    ui.horizontal(|ui| {
+        let selected = presets
+            .presets
+            .get(presets.selected)
+            .map(|preset| preset.name.clone())
+            .unwrap_or_default();
+        egui::ComboBox::from_id_source("presets")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                for (index, preset) in presets.presets.iter().enumerate() {
+                    ui.selectable_value(&mut presets.selected, index, &preset.name);
+                }
+            });
+        if ui.button("load").clicked() {
+            presets.apply(universe);
+        }
+    });
+    ui.add(egui::Slider::new(&mut presets.blend_seconds, 0.0..=10.0).text("blend seconds"));
+
+    ui.horizontal(|ui| {
+        ui.text_edit_singleline(&mut presets.name);
+        if ui.button("save current").clicked() && !presets.name.is_empty() {
+            let preset = Preset::capture(&presets.name, universe);
+            match presets
+                .presets
+                .iter()
+                .position(|other| other.name == preset.name)
+            {
+                Some(index) => {
+                    presets.presets[index] = preset;
+                    presets.selected = index;
+                }
+                None => {
+                    presets.presets.push(preset);
+                    presets.selected = presets.presets.len() - 1;
+                }
+            }
+            presets.status = presets.save().err();
+        }
+    });
+
+    if let Some(status) = &presets.status {
+        ui.colored_label(egui::Color32::RED, status);
+    }
+}
@@ -0,0 +1,756 @@
+//! recording runs tick by tick into a compact binary file and playing them back
+//!
+//! The file starts with `MAGIC`, a version and the table of param names,
+//! followed by records, all little endian:
+//!
+//! ```text
+//! PARAM    u8 tag, u16 param index, f32 value
+//! SETTINGS u8 tag, u8 steering, u8 border, u8 long range, 4 * u8 species rule row by row
+//! SPAWN    u8 tag, u64 entity
+//! DESPAWN  u8 tag, u64 entity
+//! FRAME    u8 tag, u64 tick, u32 count, count * (u64 entity, 4 * f32 position and velocity, u8 species)
+//! ```
+//!
+//! Param, settings, spawn and despawn records belong to the frame following
+//! them. Modes and rules are stored as their index in the `ALL` list of
+//! their type.
+//!
+//! A loaded replay keeps the file as it is, 25 bytes per boid and tick, and
+//! only decodes the frame on screen. Loading checks every record once and
+//! remembers where each frame starts, with the params and settings as of
+//! every `CHECKPOINT_EVERY`th frame so scrubbing doesn't start over from
+//! the first one.
+
+use std::{
+    f32::consts::PI,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+
+use bevy::{
+    prelude::*,
+    utils::{HashMap, HashSet},
+};
+use bevy_inspector_egui::{bevy_egui::EguiContexts, egui};
+
+use crate::{
+    components::{Body, Boid, Collider, Velocity},
+    resources::{BoidAssets, BoidUniverse, BorderMode, SimTick, SteeringMode},
+    spatial::SpatialIndex,
+    species::{Species, SpeciesRule},
+    systems::{advance_tick, spawn_boid, vision_region},
+    BOID_SIZE,
+};
+
+const MAGIC: &[u8; 8] = b"BOIDREC\0";
+const VERSION: u16 = 2;
+
+const FRAME: u8 = 1;
+const PARAM: u8 = 2;
+const SPAWN: u8 = 3;
+const DESPAWN: u8 = 4;
+const SETTINGS: u8 = 5;
+
+/// bytes per boid in a frame record
+const BOID_LEN: usize = 8 + 4 * 4 + 1;
+/// frames between two param and settings checkpoints
+const CHECKPOINT_EVERY: usize = 256;
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ReplayUi::default());
+        app.add_systems(
+            FixedUpdate,
+            (
+                record_tick
+                    .after(advance_tick)
+                    .run_if(resource_exists::<Recorder>()),
+                step_replay.run_if(resource_exists::<Replay>()),
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                replay_controls,
+                apply_replay.run_if(resource_exists::<Replay>()),
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoidState {
+    pub entity: u64,
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub species: Species,
+}
+
+/// the choices of the control window that aren't `PARAM_NAMES` sliders
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplaySettings {
+    pub steering: SteeringMode,
+    pub border: BorderMode,
+    pub long_range: bool,
+    pub rules: [[SpeciesRule; 2]; 2],
+}
+
+impl ReplaySettings {
+    pub fn of(universe: &BoidUniverse) -> Self {
+        Self {
+            steering: universe.steering,
+            border: universe.border,
+            long_range: universe.long_range,
+            rules: universe.rules,
+        }
+    }
+
+    pub fn apply(&self, universe: &mut BoidUniverse) {
+        universe.steering = self.steering;
+        universe.border = self.border;
+        universe.long_range = self.long_range;
+        universe.rules = self.rules;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayEvent {
+    /// index into `Replay::params`
+    Param(u16, f32),
+    Settings(ReplaySettings),
+    Spawn(u64),
+    Despawn(u64),
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame {
+    pub tick: u64,
+    pub boids: Vec<BoidState>,
+    pub events: Vec<ReplayEvent>,
+}
+
+/// writes a frame per physics tick while it exists
+#[derive(Resource)]
+pub struct Recorder<W: Write + Send + Sync + 'static = BufWriter<File>> {
+    writer: W,
+    entities: HashSet<u64>,
+    params: Vec<Option<f32>>,
+    settings: Option<ReplaySettings>,
+}
+
+impl Recorder {
+    pub fn create(path: &str) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write + Send + Sync + 'static> Recorder<W> {
+    /// writes the header, records follow with every `write_tick`
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(BoidUniverse::PARAM_NAMES.len() as u16).to_le_bytes())?;
+        for name in BoidUniverse::PARAM_NAMES {
+            writer.write_all(&[name.len() as u8])?;
+            writer.write_all(name.as_bytes())?;
+        }
+
+        Ok(Self {
+            writer,
+            entities: HashSet::new(),
+            params: vec![None; BoidUniverse::PARAM_NAMES.len()],
+            settings: None,
+        })
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// params and settings that changed since the last tick and spawns and
+    /// despawns, followed by the frame itself
+    pub(crate) fn write_tick(
+        &mut self,
+        universe: &BoidUniverse,
+        tick: u64,
+        boids: &[BoidState],
+    ) -> io::Result<()> {
+        for (index, name) in BoidUniverse::PARAM_NAMES.iter().enumerate() {
+            let value = universe.param(name);
+            if value != self.params[index] {
+                self.params[index] = value;
+                self.writer.write_all(&[PARAM])?;
+                self.writer.write_all(&(index as u16).to_le_bytes())?;
+                self.writer
+                    .write_all(&value.unwrap_or_default().to_le_bytes())?;
+            }
+        }
+
+        let settings = ReplaySettings::of(universe);
+        if self.settings != Some(settings) {
+            self.settings = Some(settings);
+            self.writer.write_all(&[
+                SETTINGS,
+                position(&SteeringMode::ALL, settings.steering),
+                position(&BorderMode::ALL, settings.border),
+                settings.long_range as u8,
+            ])?;
+            for rule in settings.rules.iter().flatten() {
+                self.writer
+                    .write_all(&[position(&SpeciesRule::ALL, *rule)])?;
+            }
+        }
+
+        let entities: HashSet<u64> = boids.iter().map(|boid| boid.entity).collect();
+        for entity in boids.iter().map(|boid| boid.entity) {
+            if !self.entities.contains(&entity) {
+                self.writer.write_all(&[SPAWN])?;
+                self.writer.write_all(&entity.to_le_bytes())?;
+            }
+        }
+        for entity in self.entities.difference(&entities) {
+            self.writer.write_all(&[DESPAWN])?;
+            self.writer.write_all(&entity.to_le_bytes())?;
+        }
+        self.entities = entities;
+
+        self.writer.write_all(&[FRAME])?;
+        self.writer.write_all(&tick.to_le_bytes())?;
+        self.writer.write_all(&(boids.len() as u32).to_le_bytes())?;
+        for boid in boids {
+            self.writer.write_all(&boid.entity.to_le_bytes())?;
+            for value in [
+                boid.position.x,
+                boid.position.y,
+                boid.velocity.x,
+                boid.velocity.y,
+            ] {
+                self.writer.write_all(&value.to_le_bytes())?;
+            }
+            self.writer.write_all(&[boid.species.index() as u8])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// index of `value` in one of the `ALL` lists
+fn position<T: PartialEq>(all: &[T], value: T) -> u8 {
+    all.iter()
+        .position(|other| *other == value)
+        .unwrap_or_default() as u8
+}
+
+/// where a frame starts in the recording, its param, settings, spawn and
+/// despawn records come first
+#[derive(Debug, Clone, Copy)]
+struct FrameEntry {
+    at: usize,
+    tick: u64,
+    boids: u32,
+}
+
+/// the params and settings as of one frame
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    params: Vec<Option<f32>>,
+    settings: Option<ReplaySettings>,
+}
+
+impl Checkpoint {
+    fn apply(&mut self, events: &[ReplayEvent]) {
+        events.iter().for_each(|event| match event {
+            ReplayEvent::Param(param, value) => {
+                if let Some(slot) = self.params.get_mut(*param as usize) {
+                    *slot = Some(*value);
+                }
+            }
+            ReplayEvent::Settings(settings) => self.settings = Some(*settings),
+            ReplayEvent::Spawn(_) | ReplayEvent::Despawn(_) => {}
+        });
+    }
+}
+
+/// a loaded recording, while it exists it drives the boids instead of the physics
+#[derive(Resource)]
+pub struct Replay {
+    pub params: Vec<String>,
+    bytes: Vec<u8>,
+    frames: Vec<FrameEntry>,
+    /// state after every `CHECKPOINT_EVERY`th frame, starting with the first
+    checkpoints: Vec<Checkpoint>,
+    pub index: usize,
+    pub playing: bool,
+    /// frame the boids currently show
+    applied: Option<usize>,
+    /// recorded entity to the one standing in for it
+    live: HashMap<u64, Entity>,
+}
+
+impl Replay {
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+        Self::decode(bytes)
+    }
+
+    /// checks every record and indexes the frames, without decoding the boids
+    pub fn decode(bytes: impl Into<Vec<u8>>) -> Result<Self, String> {
+        let bytes = bytes.into();
+        let mut reader = Reader {
+            bytes: &bytes,
+            at: 0,
+        };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err("not a boid recording".to_string());
+        }
+        let version = reader.u16()?;
+        if version != VERSION {
+            return Err(format!("unsupported recording version {}", version));
+        }
+
+        let params = (0..reader.u16()?)
+            .map(|_| {
+                let len = reader.u8()? as usize;
+                String::from_utf8(reader.take(len)?.to_vec()).map_err(|err| err.to_string())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut frames = Vec::new();
+        let mut checkpoints = Vec::new();
+        let mut state = Checkpoint {
+            params: vec![None; params.len()],
+            settings: None,
+        };
+        while !reader.is_empty() {
+            let at = reader.at;
+            state.apply(&reader.events()?);
+            let (tick, boids) = (reader.u64()?, reader.u32()?);
+            for _ in 0..boids {
+                reader.take(BOID_LEN - 1)?;
+                reader.pick(&Species::ALL)?;
+            }
+
+            if frames.len() % CHECKPOINT_EVERY == 0 {
+                checkpoints.push(state.clone());
+            }
+            frames.push(FrameEntry { at, tick, boids });
+        }
+
+        if frames.is_empty() {
+            return Err("recording holds no frames".to_string());
+        }
+
+        Ok(Self {
+            params,
+            bytes,
+            frames,
+            checkpoints,
+            index: 0,
+            playing: false,
+            applied: None,
+            live: HashMap::new(),
+        })
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn tick(&self, index: usize) -> u64 {
+        self.frames[index].tick
+    }
+
+    pub fn boid_count(&self, index: usize) -> usize {
+        self.frames[index].boids as usize
+    }
+
+    /// the records leading up to the frame at `index`
+    pub fn events(&self, index: usize) -> Vec<ReplayEvent> {
+        self.reader(index)
+            .events()
+            .expect("records are checked on load")
+    }
+
+    /// the frame at `index`, decoded from the recording
+    pub fn frame(&self, index: usize) -> Frame {
+        let mut reader = self.reader(index);
+        let events = reader.events().expect("records are checked on load");
+        let (tick, count) = (self.frames[index].tick, self.frames[index].boids);
+        reader.take(8 + 4).expect("records are checked on load");
+        let boids = (0..count)
+            .map(|_| {
+                Ok(BoidState {
+                    entity: reader.u64()?,
+                    position: Vec2::new(reader.f32()?, reader.f32()?),
+                    velocity: Vec2::new(reader.f32()?, reader.f32()?),
+                    species: reader.pick(&Species::ALL)?,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .expect("records are checked on load");
+
+        Frame {
+            tick,
+            boids,
+            events,
+        }
+    }
+
+    fn reader(&self, index: usize) -> Reader<'_> {
+        Reader {
+            bytes: &self.bytes,
+            at: self.frames[index].at,
+        }
+    }
+
+    /// params and settings as of `index`, from the checkpoint before it
+    fn state_at(&self, index: usize) -> Checkpoint {
+        let from = index / CHECKPOINT_EVERY * CHECKPOINT_EVERY;
+        let mut state = self.checkpoints[from / CHECKPOINT_EVERY].clone();
+        (from + 1..=index).for_each(|frame| state.apply(&self.events(frame)));
+        state
+    }
+
+    /// the latest recorded value of every param at `index`
+    pub(crate) fn params_at(&self, index: usize) -> Vec<(&str, f32)> {
+        self.params
+            .iter()
+            .zip(self.state_at(index).params)
+            .filter_map(|(name, value)| value.map(|value| (name.as_str(), value)))
+            .collect()
+    }
+
+    /// the latest recorded settings at `index`
+    pub(crate) fn settings_at(&self, index: usize) -> Option<ReplaySettings> {
+        self.state_at(index).settings
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn is_empty(&self) -> bool {
+        self.at >= self.bytes.len()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .bytes
+            .get(self.at..self.at + len)
+            .ok_or("recording ends early")?;
+        self.at += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, String> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// param, settings, spawn and despawn records up to and including the
+    /// tag of the next frame record
+    fn events(&mut self) -> Result<Vec<ReplayEvent>, String> {
+        let mut events = Vec::new();
+        loop {
+            match self.u8()? {
+                PARAM => events.push(ReplayEvent::Param(self.u16()?, self.f32()?)),
+                SETTINGS => {
+                    let steering = self.pick(&SteeringMode::ALL)?;
+                    let border = self.pick(&BorderMode::ALL)?;
+                    let long_range = self.u8()? != 0;
+                    let mut rules = [[SpeciesRule::Ignore; 2]; 2];
+                    for rule in rules.iter_mut().flatten() {
+                        *rule = self.pick(&SpeciesRule::ALL)?;
+                    }
+                    events.push(ReplayEvent::Settings(ReplaySettings {
+                        steering,
+                        border,
+                        long_range,
+                        rules,
+                    }));
+                }
+                SPAWN => events.push(ReplayEvent::Spawn(self.u64()?)),
+                DESPAWN => events.push(ReplayEvent::Despawn(self.u64()?)),
+                FRAME => return Ok(events),
+                tag => return Err(format!("unknown record {} at byte {}", tag, self.at - 1)),
+            }
+        }
+    }
+
+    /// an entry of one of the `ALL` lists by its u8 index
+    fn pick<T: Copy>(&mut self, all: &[T]) -> Result<T, String> {
+        let at = self.at;
+        let index = self.u8()? as usize;
+        all.get(index)
+            .copied()
+            .ok_or_else(|| format!("unknown index {} at byte {}", index, at))
+    }
+}
+
+#[derive(Resource)]
+pub struct ReplayUi {
+    pub path: String,
+    pub error: Option<String>,
+}
+
+impl Default for ReplayUi {
+    fn default() -> Self {
+        Self {
+            path: "recording.boids".to_string(),
+            error: None,
+        }
+    }
+}
+
+fn record_tick(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform, &Velocity, &Species), With<Boid>>,
+    universe: Res<BoidUniverse>,
+    tick: Res<SimTick>,
+    mut recorder: ResMut<Recorder>,
+    mut ui: ResMut<ReplayUi>,
+) {
+    let boids: Vec<BoidState> = query
+        .iter()
+        .map(|(entity, transform, velocity, species)| BoidState {
+            entity: entity.to_bits(),
+            position: transform.translation.truncate(),
+            velocity: velocity.value.truncate(),
+            species: *species,
+        })
+        .collect();
+
+    if let Err(err) = recorder.write_tick(&universe, tick.0, &boids) {
+        ui.error = Some(format!("recording stopped: {}", err));
+        commands.remove_resource::<Recorder>();
+    }
+}
+
+fn step_replay(mut replay: ResMut<Replay>) {
+    if !replay.playing {
+        return;
+    }
+
+    if replay.index + 1 < replay.frame_count() {
+        replay.index += 1;
+    } else {
+        replay.playing = false;
+    }
+}
+
+/// moves the stand-in boids to the current frame, spawning and despawning
+/// so they match it. The boid index and neighbour counts follow right
+/// away, as the physics that would update them is paused.
+fn apply_replay(
+    mut commands: Commands,
+    mut replay: ResMut<Replay>,
+    mut boids: Query<(&mut Transform, &mut Velocity, &mut Collider), With<Boid>>,
+    mut universe: ResMut<BoidUniverse>,
+    mut boid_index: ResMut<SpatialIndex<Boid>>,
+    boid_assets: Res<BoidAssets>,
+) {
+    if replay.applied == Some(replay.index) {
+        return;
+    }
+
+    let index = replay.index;
+    replay.params_at(index).iter().for_each(|(name, value)| {
+        if let Some(param) = universe.param_mut(name) {
+            *param = *value;
+        }
+    });
+    if let Some(settings) = replay.settings_at(index) {
+        settings.apply(&mut universe);
+    }
+
+    let replay = &mut *replay;
+    let frame = replay.frame(index);
+    let recorded: HashSet<u64> = frame.boids.iter().map(|boid| boid.entity).collect();
+    replay.live.retain(|entity, live| {
+        let keep = recorded.contains(entity);
+        if !keep {
+            boid_index.remove(*live);
+            commands.entity(*live).despawn_recursive();
+        }
+        keep
+    });
+
+    let stand_ins: Vec<(Entity, Vec3, Vec3)> = frame
+        .boids
+        .iter()
+        .map(|boid| {
+            let position = boid.position.extend(0.0);
+            let velocity = boid.velocity.extend(0.0);
+            let live = match replay.live.get(&boid.entity) {
+                Some(live) => *live,
+                None => {
+                    let live = spawn_boid(
+                        &mut commands,
+                        &boid_assets,
+                        boid.species,
+                        position,
+                        velocity,
+                    );
+                    replay.live.insert(boid.entity, live);
+                    live
+                }
+            };
+            if let Ok((mut transform, mut current, _)) = boids.get_mut(live) {
+                let direction = velocity.normalize_or_zero();
+                transform.translation = position;
+                transform.rotation =
+                    Quat::from_rotation_z(-direction.x.atan2(direction.y) + PI / 2.0);
+                current.value = velocity;
+            }
+            boid_index.insert(
+                live,
+                Collider::new(BOID_SIZE).into_region(position),
+                Body {
+                    entity: live,
+                    species: boid.species,
+                    position,
+                    velocity,
+                },
+            );
+            (live, position, velocity)
+        })
+        .collect();
+
+    stand_ins.iter().for_each(|&(live, position, velocity)| {
+        let mut collider = Collider::new(BOID_SIZE);
+        collider.nearby = boid_index
+            .query(&vision_region(&universe, &collider, position), &[live])
+            .iter()
+            .filter(|body| universe.perception.sees(velocity, body.position - position))
+            .count();
+        match boids.get_mut(live) {
+            Ok((_, _, mut current)) => current.nearby = collider.nearby,
+            Err(_) => {
+                commands.entity(live).insert(collider);
+            }
+        }
+    });
+
+    replay.applied = Some(index);
+}
+
+fn replay_controls(
+    mut commands: Commands,
+    mut context: EguiContexts,
+    mut ui_state: ResMut<ReplayUi>,
+    recorder: Option<Res<Recorder>>,
+    mut replay: Option<ResMut<Replay>>,
+    boids: Query<Entity, With<Boid>>,
+) {
+    egui::Window::new("Replay")
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::Vec2::new(0.0, 0.0))
+        .show(context.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut ui_state.path);
+            });
+
+            ui.horizontal(|ui| {
+                match (&recorder, &replay) {
+                    (Some(_), _) => {
+                        if ui.button("stop recording").clicked() {
+                            commands.add(|world: &mut World| {
+                                let Some(recorder) = world.remove_resource::<Recorder>() else {
+                                    return;
+                                };
+                                if let Err(err) = recorder.finish() {
+                                    world.resource_mut::<ReplayUi>().error = Some(err.to_string());
+                                }
+                            });
+                        }
+                    }
+                    (None, None) => {
+                        if ui.button("record").clicked() {
+                            match Recorder::create(&ui_state.path) {
+                                Ok(recorder) => {
+                                    commands.insert_resource(recorder);
+                                    ui_state.error = None;
+                                }
+                                Err(err) => ui_state.error = Some(err.to_string()),
+                            }
+                        }
+                        if ui.button("replay").clicked() {
+                            match Replay::load(&ui_state.path) {
+                                Ok(replay) => {
+                                    // the recording brings its own boids
+                                    boids.iter().for_each(|entity| {
+                                        commands.entity(entity).despawn_recursive()
+                                    });
+                                    commands.insert_resource(replay);
+                                    ui_state.error = None;
+                                }
+                                Err(err) => ui_state.error = Some(err),
+                            }
+                        }
+                    }
+                    (None, Some(_)) => {
+                        // the stand-ins stay and the simulation carries on from them
+                        if ui.button("close replay").clicked() {
+                            commands.remove_resource::<Replay>();
+                        }
+                    }
+                }
+            });
+
+            if let Some(replay) = replay.as_mut() {
+                let last = replay.frame_count() - 1;
+                ui.horizontal(|ui| {
+                    let label = if replay.playing { "pause" } else { "play" };
+                    if ui.button(label).clicked() {
+                        replay.playing = !replay.playing;
+                        if replay.playing && replay.index == last {
+                            replay.index = 0;
+                        }
+                    }
+                    ui.add(egui::Slider::new(&mut replay.index, 0..=last).text("frame"));
+                });
+
+                let index = replay.index;
+                ui.label(format!(
+                    "tick {}, {} boids",
+                    replay.tick(index),
+                    replay.boid_count(index)
+                ));
+                let (spawns, despawns) = replay.events(index).iter().fold(
+                    (0, 0),
+                    |(spawns, despawns), event| match event {
+                        ReplayEvent::Spawn(_) => (spawns + 1, despawns),
+                        ReplayEvent::Despawn(_) => (spawns, despawns + 1),
+                        ReplayEvent::Param(..) | ReplayEvent::Settings(_) => (spawns, despawns),
+                    },
+                );
+                ui.label(format!("{} spawned, {} despawned", spawns, despawns));
+            }
+
+            if let Some(error) = &ui_state.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+        });
+}
@@ -0,0 +1,386 @@
+use bevy::{prelude::*, sprite::Mesh2dHandle};
+use rand::{rngs::StdRng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use quadtree::{coord::Coord, region::Region};
+use super::components::*;
+use super::species::{default_rules, Species, SpeciesParams, SpeciesRule};
+use crate::BOID_SIZE;
+
+/// shared materials `BoidAssets::tint` picks from
+pub const TINT_COUNT: usize = 32;
+
+#[derive(Resource)]
+pub struct BoidUniverse {
+    /// the world, boids and obstacles live in a `SpatialIndex` of the same size
+    pub bounds: Region,
+    /// indexed by `Species::index`
+    pub species: [SpeciesParams; 2],
+    pub rules: [[SpeciesRule; 2]; 2],
+    pub spawn_species: Species,
+    pub vision: f32,
+    pub speed: f32,
+    pub steering: SteeringMode,
+    pub separation_radius: f32,
+    pub cohesion_radius: f32,
+    pub alignment_radius: f32,
+    pub perception: Perception,
+    pub min_speed: f32,
+    pub max_force: f32,
+    /// degrees per second
+    pub max_turn_rate: f32,
+    pub drag: f32,
+    pub avoidance: f32,
+    pub avoidance_distance: f32,
+    pub border: BorderMode,
+    /// distance from the edge where bouncing and steering kick in
+    pub border_margin: f32,
+    pub border_force: f32,
+    pub long_range: bool,
+    pub long_range_theta: f32,
+    pub long_range_strength: f32,
+    pub show_graph: bool,
+    pub show_trails: bool,
+    /// positions drawn per trail, up to `MAX_TRAIL_LEN`
+    pub trail_length: usize,
+    /// opacity kept from one trail point to the next older one
+    pub trail_decay: f32,
+    pub trail_subset: TrailSubset,
+    pub color_mode: ColorMode,
+    pub mouse_tool: MouseTool,
+    pub obstacle: Obstacle,
+    /// acceleration the force tools apply at the cursor center
+    pub cursor_strength: f32,
+    /// reach of the force tools, the field fades out towards it
+    pub cursor_radius: f32,
+    pub boid_count : u32,
+    pub mouse_used_by_egui : bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SteeringMode {
+    /// steering forces, integrated with speed, force and turn limits
+    Reynolds,
+    /// the original rules, turning instantly while keeping the speed
+    Legacy,
+}
+
+impl SteeringMode {
+    pub const ALL: [SteeringMode; 2] = [SteeringMode::Reynolds, SteeringMode::Legacy];
+}
+
+/// what the colour of a boid shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    Species,
+    Speed,
+    /// hue wheel, east is red
+    Heading,
+    /// `Collider::nearby`
+    Nearby,
+    /// one colour per group of boids linked within the cohesion radius
+    Flock,
+}
+
+impl ColorMode {
+    pub const ALL: [ColorMode; 5] = [
+        ColorMode::Species,
+        ColorMode::Speed,
+        ColorMode::Heading,
+        ColorMode::Nearby,
+        ColorMode::Flock,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorMode::Species => "species",
+            ColorMode::Speed => "speed",
+            ColorMode::Heading => "heading",
+            ColorMode::Nearby => "nearby",
+            ColorMode::Flock => "flock",
+        }
+    }
+}
+
+/// which boids leave a trail
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailSubset {
+    All,
+    /// the one picked with the middle mouse button
+    Selected,
+    /// every 10th boid by entity index, for crowded worlds
+    Sample,
+}
+
+/// what happens to boids reaching the edge of the world
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BorderMode {
+    /// flip the velocity within `border_margin` of the edge
+    Bounce,
+    /// leave on one side, come back on the other. Only the positions wrap,
+    /// boids don't see or hunt across the seam
+    Wrap,
+    /// steer inwards, harder the closer to the edge
+    Steer,
+    /// boids leaving the world jump to random positions. The entity stays,
+    /// so replays and analytics see a jump rather than a despawn and a spawn
+    Respawn,
+}
+
+impl BorderMode {
+    pub const ALL: [BorderMode; 4] = [
+        BorderMode::Bounce,
+        BorderMode::Wrap,
+        BorderMode::Steer,
+        BorderMode::Respawn,
+    ];
+}
+
+/// what left and right clicks do
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseTool {
+    /// spawn and despawn boids
+    Boids,
+    /// place and remove obstacles
+    Obstacles,
+    /// pull boids towards the cursor while held
+    Attract,
+    /// push boids away from the cursor while held
+    Repel,
+    /// swirl boids around the cursor while held
+    Vortex,
+}
+
+impl MouseTool {
+    /// tools applying a force field instead of spawning or removing
+    pub fn is_force(&self) -> bool {
+        matches!(
+            self,
+            MouseTool::Attract | MouseTool::Repel | MouseTool::Vortex
+        )
+    }
+}
+
+/// the one source of randomness in the simulation, so a seed and the same
+/// entity order reproduce a run exactly
+#[derive(Resource)]
+pub struct SimRng {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SimRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for SimRng {
+    fn default() -> Self {
+        Self::new(crate::DEFAULT_SEED)
+    }
+}
+
+impl std::ops::Deref for SimRng {
+    type Target = StdRng;
+
+    fn deref(&self) -> &Self::Target {
+        &self.rng
+    }
+}
+
+impl std::ops::DerefMut for SimRng {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.rng
+    }
+}
+
+/// physics ticks run so far
+#[derive(Resource, Default)]
+pub struct SimTick(pub u64);
+
+/// boid picked with the middle mouse button, its vision cone gets drawn
+#[derive(Resource, Default)]
+pub struct SelectedBoid(pub Option<Entity>);
+
+/// the mesh and the few materials every boid shares, so spawning adds no
+/// assets and all boids render in one batch per material
+#[derive(Resource)]
+pub struct BoidAssets {
+    pub mesh: Mesh2dHandle,
+    species: [Handle<ColorMaterial>; 2],
+    tints: Vec<Handle<ColorMaterial>>,
+}
+
+impl BoidAssets {
+    pub fn new(
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<ColorMaterial>,
+        universe: &BoidUniverse,
+    ) -> Self {
+        Self {
+            mesh: meshes
+                .add(Mesh::from(shape::Quad::new(Vec2::new(
+                    BOID_SIZE,
+                    BOID_SIZE / 2.0,
+                ))))
+                .into(),
+            species: Species::ALL
+                .map(|species| materials.add(ColorMaterial::from(universe.params(species).color))),
+            tints: (0..TINT_COUNT)
+                .map(|step| {
+                    let hue = step as f32 / TINT_COUNT as f32 * 360.0;
+                    // as bright as the species colours so tinted boids glow the same
+                    materials.add(ColorMaterial::from(
+                        Color::hsl(hue, 1.0, 0.5).as_rgba() * 2.0,
+                    ))
+                })
+                .collect(),
+        }
+    }
+
+    pub fn species(&self, species: Species) -> Handle<ColorMaterial> {
+        self.species[species.index()].clone()
+    }
+
+    /// the shared material closest to `hue`, 0 to 1 once around the colour wheel
+    pub fn tint(&self, hue: f32) -> Handle<ColorMaterial> {
+        let step = (hue.rem_euclid(1.0) * TINT_COUNT as f32).round() as usize % TINT_COUNT;
+        self.tints[step].clone()
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct QuadBench {
+    pub avarage_query_time: u128,
+    pub avarage_build_time: u128,
+    pub avarage_long_range_time: u128,
+}
+
+impl BoidUniverse {
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        let _min = Coord::from_f32(min.x, min.y);
+        let _max = Coord::from_f32(max.x, max.y);
+        Self {
+            bounds: Region::new(_min, _max),
+            species: [SpeciesParams::prey(), SpeciesParams::predator()],
+            rules: default_rules(),
+            spawn_species: Species::Prey,
+            speed: 1.0,
+            vision : 1.0,
+            steering: SteeringMode::Reynolds,
+            separation_radius: 10.0,
+            cohesion_radius: 30.0,
+            alignment_radius: 20.0,
+            perception: Perception {
+                vision_angle: 360.0,
+                blind_spot: 90.0,
+            },
+            min_speed: 80.0,
+            max_force: 1000.0,
+            max_turn_rate: 360.0,
+            drag: 0.1,
+            avoidance: 1.0,
+            avoidance_distance: 60.0,
+            border: BorderMode::Bounce,
+            border_margin: 20.0,
+            border_force: 1.0,
+            long_range: false,
+            long_range_theta: 0.8,
+            long_range_strength: 0.05,
+            mouse_tool: MouseTool::Boids,
+            obstacle: Obstacle::CIRCLE,
+            cursor_strength: 600.0,
+            cursor_radius: 100.0,
+            boid_count : 0,
+            show_graph: true,
+            show_trails: false,
+            trail_length: 30,
+            trail_decay: 0.93,
+            trail_subset: TrailSubset::All,
+            color_mode: ColorMode::Species,
+            mouse_used_by_egui : false,
+        }
+    }
+
+    pub fn params(&self, species: Species) -> &SpeciesParams {
+        &self.species[species.index()]
+    }
+
+    pub fn rule(&self, species: Species, other: Species) -> SpeciesRule {
+        self.rules[species.index()][other.index()]
+    }
+
+    /// widest radius any steering rule looks at
+    pub fn max_radius(&self) -> f32 {
+        self.separation_radius
+            .max(self.cohesion_radius)
+            .max(self.alignment_radius)
+    }
+}
+
+/// every slider of the control window by name, species ones are prefixed
+/// like `prey.cohesion`
+macro_rules! params {
+    ($($name:literal => [$($field:tt)+],)*) => {
+        impl BoidUniverse {
+            pub const PARAM_NAMES: &'static [&'static str] = &[$($name),*];
+
+            pub fn param(&self, name: &str) -> Option<f32> {
+                match name {
+                    $($name => Some(self.$($field)+),)*
+                    _ => None,
+                }
+            }
+
+            pub fn param_mut(&mut self, name: &str) -> Option<&mut f32> {
+                match name {
+                    $($name => Some(&mut self.$($field)+),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+params! {
+    "speed" => [speed],
+    "vision" => [vision],
+    "separation_radius" => [separation_radius],
+    "cohesion_radius" => [cohesion_radius],
+    "alignment_radius" => [alignment_radius],
+    "vision_angle" => [perception.vision_angle],
+    "blind_spot" => [perception.blind_spot],
+    "min_speed" => [min_speed],
+    "max_force" => [max_force],
+    "max_turn_rate" => [max_turn_rate],
+    "drag" => [drag],
+    "avoidance" => [avoidance],
+    "avoidance_distance" => [avoidance_distance],
+    "border_margin" => [border_margin],
+    "border_force" => [border_force],
+    "long_range_theta" => [long_range_theta],
+    "long_range_strength" => [long_range_strength],
+    "cursor_strength" => [cursor_strength],
+    "cursor_radius" => [cursor_radius],
+    "prey.separation" => [species[0].separation],
+    "prey.cohesion" => [species[0].cohesion],
+    "prey.alignment" => [species[0].alignment],
+    "prey.max_speed" => [species[0].max_speed],
+    "prey.flee" => [species[0].flee],
+    "prey.chase" => [species[0].chase],
+    "prey.hunt_radius" => [species[0].hunt_radius],
+    "predator.separation" => [species[1].separation],
+    "predator.cohesion" => [species[1].cohesion],
+    "predator.alignment" => [species[1].alignment],
+    "predator.max_speed" => [species[1].max_speed],
+    "predator.flee" => [species[1].flee],
+    "predator.chase" => [species[1].chase],
+    "predator.hunt_radius" => [species[1].hunt_radius],
+}
@@ -0,0 +1,259 @@
+//! the whole simulation state as a RON file, to restore a configuration
+//! instead of starting from random boids
+
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_inspector_egui::{bevy_egui::EguiContexts, egui};
+use quadtree::{coord::Coord, region::Region};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{Boid, Collider, Obstacle, Velocity},
+    ecology::{Energy, Genome},
+    init::insert_indexes,
+    replay::Replay,
+    resources::{BoidAssets, BoidUniverse, BorderMode, SelectedBoid, SteeringMode},
+    species::{Species, SpeciesRule},
+    systems::spawn_boid,
+};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// world bounds, min and max corner
+    pub bounds: (Vec2, Vec2),
+    /// every `BoidUniverse::PARAM_NAMES` entry
+    pub params: BTreeMap<String, f32>,
+    pub steering: SteeringMode,
+    pub border: BorderMode,
+    pub long_range: bool,
+    pub rules: [[SpeciesRule; 2]; 2],
+    pub boids: Vec<BoidSnapshot>,
+    pub obstacles: Vec<ObstacleSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoidSnapshot {
+    pub species: Species,
+    pub transform: Transform,
+    pub velocity: Vec3,
+    pub radius: f32,
+    /// only boids that lived through an ecology tick have energy and genes
+    #[serde(default)]
+    pub energy: Option<f32>,
+    #[serde(default)]
+    pub genome: Option<Genome>,
+}
+
+/// the components of a boid a snapshot keeps
+pub type SnapshotBoid<'a> = (
+    &'a Transform,
+    &'a Velocity,
+    &'a Collider,
+    &'a Species,
+    Option<&'a Energy>,
+    Option<&'a Genome>,
+);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObstacleSnapshot {
+    pub transform: Transform,
+    pub obstacle: Obstacle,
+}
+
+impl Snapshot {
+    pub fn capture<'a>(
+        universe: &BoidUniverse,
+        boids: impl Iterator<Item = SnapshotBoid<'a>>,
+        obstacles: impl Iterator<Item = (&'a Transform, &'a Obstacle)>,
+    ) -> Self {
+        let (min_x, min_y, max_x, max_y) = universe.bounds.into_f32();
+        Self {
+            bounds: (Vec2::new(min_x, min_y), Vec2::new(max_x, max_y)),
+            params: BoidUniverse::PARAM_NAMES
+                .iter()
+                .filter_map(|name| Some((name.to_string(), universe.param(name)?)))
+                .collect(),
+            steering: universe.steering,
+            border: universe.border,
+            long_range: universe.long_range,
+            rules: universe.rules,
+            boids: boids
+                .map(
+                    |(transform, velocity, collider, species, energy, genome)| BoidSnapshot {
+                        species: *species,
+                        transform: *transform,
+                        velocity: velocity.value,
+                        radius: collider.radius,
+                        energy: energy.map(|energy| energy.0),
+                        genome: genome.copied(),
+                    },
+                )
+                .collect(),
+            obstacles: obstacles
+                .map(|(transform, obstacle)| ObstacleSnapshot {
+                    transform: *transform,
+                    obstacle: *obstacle,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|err| format!("can't read {}: {}", path, err))?;
+        ron::from_str(&text).map_err(|err| format!("{}: {}", path, err))
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let text = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())?;
+        std::fs::write(path, text).map_err(|err| format!("can't write {}: {}", path, err))
+    }
+
+    /// a fresh universe with the saved bounds and settings
+    pub fn universe(&self) -> BoidUniverse {
+        let mut universe = BoidUniverse::new(self.bounds.0, self.bounds.1);
+        self.apply(&mut universe);
+        universe
+    }
+
+    /// the saved bounds and settings, what only the window shows stays
+    pub fn apply(&self, universe: &mut BoidUniverse) {
+        let (min, max) = self.bounds;
+        universe.bounds = Region::new(Coord::from_f32(min.x, min.y), Coord::from_f32(max.x, max.y));
+        self.params.iter().for_each(|(name, value)| {
+            if let Some(param) = universe.param_mut(name) {
+                *param = *value;
+            }
+        });
+        universe.steering = self.steering;
+        universe.border = self.border;
+        universe.long_range = self.long_range;
+        universe.rules = self.rules;
+    }
+
+    /// spawns everything into empty indexes for the universe's bounds,
+    /// `spawn` creates a boid which then gets the saved transform and collider
+    pub fn restore(
+        &self,
+        commands: &mut Commands,
+        universe: &BoidUniverse,
+        mut spawn: impl FnMut(&mut Commands, &BoidUniverse, &BoidSnapshot) -> Entity,
+    ) {
+        insert_indexes(commands, &universe.bounds);
+        self.boids.iter().for_each(|boid| {
+            let entity = spawn(commands, universe, boid);
+            let mut entity = commands.entity(entity);
+            entity
+                .insert(boid.transform)
+                .insert(Collider::new(boid.radius));
+            if let Some(energy) = boid.energy {
+                entity.insert(Energy(energy));
+            }
+            if let Some(genome) = boid.genome {
+                entity.insert(genome);
+            }
+        });
+        self.obstacles.iter().for_each(|obstacle| {
+            commands.spawn((
+                SpatialBundle::from_transform(obstacle.transform),
+                obstacle.obstacle,
+                Collider::new(obstacle.obstacle.bounding_size()),
+            ));
+        });
+    }
+}
+
+#[derive(Resource)]
+pub struct SnapshotUi {
+    pub path: String,
+    pub status: Option<String>,
+}
+
+impl Default for SnapshotUi {
+    fn default() -> Self {
+        Self {
+            path: "scene.ron".to_string(),
+            status: None,
+        }
+    }
+}
+
+/// what a snapshot is taken of and what loading one replaces
+#[derive(SystemParam)]
+pub struct SnapshotScene<'w, 's> {
+    universe: ResMut<'w, BoidUniverse>,
+    selected: ResMut<'w, SelectedBoid>,
+    boids: Query<'w, 's, (Entity, SnapshotBoid<'static>), With<Boid>>,
+    obstacles: Query<'w, 's, (Entity, &'static Transform, &'static Obstacle)>,
+}
+
+pub fn snapshot_controls(
+    mut commands: Commands,
+    mut context: EguiContexts,
+    mut ui_state: ResMut<SnapshotUi>,
+    mut scene: SnapshotScene,
+    boid_assets: Res<BoidAssets>,
+) {
+    egui::Window::new("Scene")
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::Vec2::new(0.0, 0.0))
+        .show(context.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label("File:");
+                ui.text_edit_singleline(&mut ui_state.path);
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("save").clicked() {
+                    let snapshot = Snapshot::capture(
+                        &scene.universe,
+                        scene.boids.iter().map(|(_, boid)| boid),
+                        scene.obstacles.iter().map(|(_, t, o)| (t, o)),
+                    );
+                    ui_state.status = Some(match snapshot.save(&ui_state.path) {
+                        Ok(()) => format!("saved {} boids", snapshot.boids.len()),
+                        Err(err) => err,
+                    });
+                }
+
+                if ui.button("load").clicked() {
+                    match Snapshot::load(&ui_state.path) {
+                        Ok(snapshot) => {
+                            scene.boids.iter().for_each(|(entity, ..)| {
+                                commands.entity(entity).despawn_recursive()
+                            });
+                            scene.obstacles.iter().for_each(|(entity, ..)| {
+                                commands.entity(entity).despawn_recursive()
+                            });
+                            commands.remove_resource::<Replay>();
+                            scene.selected.0 = None;
+
+                            // colours, trails and mouse tools stay as they are
+                            snapshot.apply(&mut scene.universe);
+                            snapshot.restore(
+                                &mut commands,
+                                &scene.universe,
+                                |commands, _, boid| {
+                                    spawn_boid(
+                                        commands,
+                                        &boid_assets,
+                                        boid.species,
+                                        boid.transform.translation,
+                                        boid.velocity,
+                                    )
+                                },
+                            );
+                            ui_state.status =
+                                Some(format!("loaded {} boids", snapshot.boids.len()));
+                        }
+                        Err(err) => ui_state.status = Some(err),
+                    }
+                }
+            });
+
+            if let Some(status) = &ui_state.status {
+                ui.label(status);
+            }
+        });
+}
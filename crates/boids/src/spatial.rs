@@ -0,0 +1,199 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::{
+        query::{QueryItem, ReadOnlyWorldQuery},
+        schedule::{InternedScheduleLabel, ScheduleLabel},
+        system::SystemParam,
+    },
+    prelude::*,
+    utils::HashMap,
+};
+
+use super::components::Collider;
+use quadtree::{
+    aggregate::Aggregate,
+    region::Region,
+    slot_map::SlotId,
+    tree::{QuadTree, QueryHit},
+};
+
+/// what a `SpatialIndex<Self>` keeps per entity and sums up per quad node
+pub trait Indexed: Component {
+    type Value: Send + Sync + 'static;
+    type Aggregate: Aggregate<Self::Value> + Send + Sync + 'static;
+    /// components the value is made of, next to the `Transform`
+    type Data: ReadOnlyWorldQuery;
+
+    fn value(entity: Entity, transform: &Transform, data: QueryItem<'_, Self::Data>)
+        -> Self::Value;
+}
+
+/// systems keeping the spatial indexes in sync, readers in the same schedule
+/// go after it
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpatialSync;
+
+/// keeps a `SpatialIndex<M>` in sync with every entity carrying `M` and a `Collider`,
+/// the index itself has to be inserted once the world bounds are known
+pub struct SpatialIndexPlugin<M: Indexed> {
+    schedule: InternedScheduleLabel,
+    marker: PhantomData<M>,
+}
+
+impl<M: Indexed> Default for SpatialIndexPlugin<M> {
+    fn default() -> Self {
+        Self::in_schedule(PostUpdate)
+    }
+}
+
+impl<M: Indexed> SpatialIndexPlugin<M> {
+    /// syncs in `schedule` instead of `PostUpdate`, for entities moved there
+    pub fn in_schedule(schedule: impl ScheduleLabel) -> Self {
+        Self {
+            schedule: schedule.intern(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M: Indexed> Plugin for SpatialIndexPlugin<M> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            self.schedule,
+            (remove_despawned::<M>, sync_changed::<M>)
+                .chain()
+                .in_set(SpatialSync)
+                .run_if(resource_exists::<SpatialIndex<M>>()),
+        );
+        // removals are only kept for two frames and a fixed schedule may
+        // skip some, so they are read once more every frame
+        if self.schedule != PostUpdate.intern() {
+            app.add_systems(
+                Last,
+                remove_despawned::<M>.run_if(resource_exists::<SpatialIndex<M>>()),
+            );
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct SpatialIndex<M: Indexed> {
+    tree: QuadTree<M::Value, M::Aggregate>,
+    slots: HashMap<Entity, SlotId>,
+    /// microseconds the last sync took
+    sync_time: u128,
+    marker: PhantomData<M>,
+}
+
+impl<M: Indexed> SpatialIndex<M> {
+    pub fn new(region: Region) -> Self {
+        Self {
+            tree: QuadTree::new(region),
+            slots: HashMap::new(),
+            sync_time: 0,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn tree(&self) -> &QuadTree<M::Value, M::Aggregate> {
+        &self.tree
+    }
+
+    pub fn sync_time(&self) -> u128 {
+        self.sync_time
+    }
+
+    pub fn insert(&mut self, entity: Entity, region: Region, value: M::Value) {
+        self.remove(entity);
+        let id = self.tree.insert(region, value);
+        self.slots.insert(entity, id);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(id) = self.slots.remove(&entity) {
+            self.tree.remove(&id);
+        }
+    }
+
+    pub fn query(&self, region: &Region, exclude: &[Entity]) -> Vec<&M::Value> {
+        self.tree.query(region, &self.exclude(exclude))
+    }
+
+    pub fn query_hits(&self, region: &Region, exclude: &[Entity]) -> Vec<QueryHit<'_, M::Value>> {
+        self.tree.query_hits(region, &self.exclude(exclude))
+    }
+
+    pub fn query_nearest(
+        &self,
+        region: &Region,
+        exclude: &[Entity],
+        limit: Option<usize>,
+    ) -> Vec<QueryHit<'_, M::Value>> {
+        self.tree
+            .query_nearest(region, &self.exclude(exclude), limit)
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    fn exclude(&self, entities: &[Entity]) -> Vec<SlotId> {
+        entities
+            .iter()
+            .filter_map(|entity| self.slots.get(entity).cloned())
+            .collect()
+    }
+}
+
+/// read access to the `SpatialIndex<M>` from any system
+#[derive(SystemParam)]
+pub struct SpatialQuery<'w, M: Indexed> {
+    index: Res<'w, SpatialIndex<M>>,
+}
+
+impl<'w, M: Indexed> std::ops::Deref for SpatialQuery<'w, M> {
+    type Target = SpatialIndex<M>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.index
+    }
+}
+
+fn remove_despawned<M: Indexed>(
+    mut index: ResMut<SpatialIndex<M>>,
+    mut removed_markers: RemovedComponents<M>,
+    mut removed_colliders: RemovedComponents<Collider>,
+) {
+    removed_markers
+        .read()
+        .chain(removed_colliders.read())
+        .for_each(|entity| index.remove(entity));
+}
+
+type MovedColliders<'w, 's, M> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static Collider,
+        <M as Indexed>::Data,
+    ),
+    (With<M>, Changed<Transform>),
+>;
+
+fn sync_changed<M: Indexed>(mut index: ResMut<SpatialIndex<M>>, query: MovedColliders<M>) {
+    let now = instant::Instant::now();
+    query
+        .iter()
+        .for_each(|(entity, transform, collider, data)| {
+            let value = M::value(entity, transform, data);
+            index.insert(entity, collider.into_region(transform.translation), value);
+        });
+    index.sync_time = now.elapsed().as_micros();
+}
@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Species {
+    Prey,
+    Predator,
+}
+
+impl Species {
+    pub const ALL: [Species; 2] = [Species::Prey, Species::Predator];
+
+    pub fn index(&self) -> usize {
+        match self {
+            Species::Prey => 0,
+            Species::Predator => 1,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Species::Prey => "prey",
+            Species::Predator => "predator",
+        }
+    }
+}
+
+/// how a boid treats a neighbour of some species
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SpeciesRule {
+    /// separation, cohesion and alignment apply
+    FlockWith,
+    Flee,
+    /// hunt the closest one within `SpeciesParams::hunt_radius`, despawning it on contact
+    Chase,
+    Ignore,
+}
+
+impl SpeciesRule {
+    pub const ALL: [SpeciesRule; 4] = [
+        SpeciesRule::FlockWith,
+        SpeciesRule::Flee,
+        SpeciesRule::Chase,
+        SpeciesRule::Ignore,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SpeciesRule::FlockWith => "flock with",
+            SpeciesRule::Flee => "flee",
+            SpeciesRule::Chase => "chase",
+            SpeciesRule::Ignore => "ignore",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SpeciesParams {
+    pub color: Color,
+    pub separation: f32,
+    pub cohesion: f32,
+    pub alignment: f32,
+    pub max_speed: f32,
+    pub flee: f32,
+    pub chase: f32,
+    pub hunt_radius: f32,
+    /// boids added per click
+    pub spawn_count: usize,
+}
+
+impl SpeciesParams {
+    pub fn prey() -> Self {
+        Self {
+            color: Color::rgb(2., 2., 0.),
+            separation: 0.1,
+            cohesion: 0.1,
+            alignment: 0.1,
+            max_speed: 300.0,
+            flee: 1.0,
+            chase: 0.0,
+            hunt_radius: 0.0,
+            spawn_count: 100,
+        }
+    }
+
+    pub fn predator() -> Self {
+        Self {
+            color: Color::rgb(3., 0.3, 0.3),
+            separation: 0.3,
+            cohesion: 0.0,
+            alignment: 0.0,
+            max_speed: 360.0,
+            flee: 0.0,
+            chase: 1.0,
+            hunt_radius: 150.0,
+            spawn_count: 5,
+        }
+    }
+}
+
+/// `rules[a][b]` is how species `a` treats species `b`
+pub fn default_rules() -> [[SpeciesRule; 2]; 2] {
+    [
+        [SpeciesRule::FlockWith, SpeciesRule::Flee],
+        [SpeciesRule::Chase, SpeciesRule::FlockWith],
+    ]
+}
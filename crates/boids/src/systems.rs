@@ -0,0 +1,794 @@
+use std::{
+    cell::RefCell,
+    f32::consts::{PI, TAU},
+};
+
+use bevy::{ecs::system::SystemParam, prelude::*, sprite::MaterialMesh2dBundle};
+
+use super::{
+    components::*,
+    ecology::Genome,
+    flow::{FlowField, FlowMode},
+    resources::{
+        BoidAssets, BorderMode, MouseTool, QuadBench, SelectedBoid, SimRng, SimTick, SteeringMode,
+    },
+    spatial::{SpatialIndex, SpatialQuery},
+    species::{Species, SpeciesParams, SpeciesRule},
+    BoidUniverse,
+};
+use crate::{BOID_SIZE, CURSOR_QUAD_SIZE};
+use quadtree::{coord::Coord, region::Region, tree::QuadTree};
+use rand::Rng;
+use thread_local::ThreadLocal;
+
+/// prey caught during the last `update_boids`, sorted. The despawns are
+/// still queued until the end of the tick, so later systems skip them.
+#[derive(Resource, Default)]
+pub struct CaughtPrey(pub Vec<Entity>);
+
+impl CaughtPrey {
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.0.binary_search(&entity).is_ok()
+    }
+}
+
+/// what one thread measured and caught during `update_boids`, summed up
+/// once the parallel pass is done
+#[derive(Default)]
+pub struct SteeringTally {
+    query_time: u128,
+    long_range_time: u128,
+    caught: Vec<Entity>,
+}
+
+type SteeringBoids<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static Transform,
+        &'static mut Collider,
+        &'static mut Velocity,
+        &'static mut Acceleration,
+        &'static Species,
+        Option<&'static Perception>,
+        Option<&'static Genome>,
+    ),
+>;
+
+/// everything the boids steer by, shared read only by the parallel pass
+#[derive(SystemParam)]
+pub struct Surroundings<'w, 's> {
+    universe: Res<'w, BoidUniverse>,
+    flow: Res<'w, FlowField>,
+    boid_index: SpatialQuery<'w, Boid>,
+    obstacles: Query<'w, 's, (&'static Transform, &'static Obstacle)>,
+    obstacle_index: SpatialQuery<'w, Obstacle>,
+}
+
+pub fn update_boids(
+    mut commands: Commands,
+    mut query: SteeringBoids,
+    surroundings: Surroundings,
+    mut caught_prey: ResMut<CaughtPrey>,
+    mut bench: ResMut<QuadBench>,
+    mut tallies: Local<ThreadLocal<RefCell<SteeringTally>>>,
+) {
+    let Surroundings {
+        universe,
+        flow,
+        boid_index,
+        obstacles,
+        obstacle_index,
+    } = &surroundings;
+    let (min_x, min_y, max_x, max_y) = universe.bounds.into_f32();
+    let (world_min, world_max) = (Vec2::new(min_x, min_y), Vec2::new(max_x, max_y));
+    query.par_iter_mut().for_each(
+        |(
+            entity,
+            transform,
+            mut collider,
+            mut velocity,
+            mut acceleration,
+            species,
+            perception,
+            genome,
+        )| {
+            let mut tally = tallies.get_or_default().borrow_mut();
+            let x = transform.translation.x as i32;
+            let y = transform.translation.y as i32;
+            let win = &universe.bounds;
+            let now = instant::Instant::now();
+
+            // -------------------- collision query --------------------
+            let query_region = vision_region(universe, &collider, transform.translation);
+
+            let position = transform.translation;
+            let perception = perception.unwrap_or(&universe.perception);
+            let mut collisions = boid_index.query(&query_region, &[entity]);
+            collisions.retain(|body| perception.sees(velocity.value, body.position - position));
+            collider.nearby = collisions.len();
+
+            tally.query_time += now.elapsed().as_nanos();
+
+            let species = *species;
+            let evolved = genome.map(|genome| genome.express(universe.params(species)));
+            let params = evolved.as_ref().unwrap_or(universe.params(species));
+            let (flockmates, others): (Vec<&Body>, Vec<&Body>) = collisions
+                .iter()
+                .partition(|body| universe.rule(species, body.species) == SpeciesRule::FlockWith);
+
+            let mut direction = velocity.value.normalize_or_zero();
+            direction += match universe.steering {
+                SteeringMode::Legacy => legacy_steering(params, &flockmates, position),
+                SteeringMode::Reynolds => {
+                    reynolds_steering(universe, params, &flockmates, position)
+                }
+            };
+
+            // -------------------- Flee --------------------
+            let flee = others
+                .iter()
+                .filter(|body| universe.rule(species, body.species) == SpeciesRule::Flee)
+                .fold(Vec3::ZERO, |acc, body| {
+                    acc + (position - body.position).normalize_or_zero()
+                });
+            if flee.length() > 0.0 {
+                direction += flee.normalize() * params.flee;
+            }
+
+            // -------------------- Chase --------------------
+            let hunts = Species::ALL
+                .iter()
+                .any(|other| universe.rule(species, *other) == SpeciesRule::Chase);
+            if hunts && params.hunt_radius > 0.0 {
+                let hunt_region = collider
+                    .into_region(position)
+                    .with_margin(params.hunt_radius as i32);
+                let target = boid_index
+                    .query_nearest(&hunt_region, &[entity], None)
+                    .into_iter()
+                    .map(|hit| hit.value)
+                    .find(|body| universe.rule(species, body.species) == SpeciesRule::Chase);
+
+                if let Some(prey) = target {
+                    let offset = prey.position - position;
+                    direction += offset.normalize_or_zero() * params.chase;
+
+                    if offset.length() < BOID_SIZE * 2.0 {
+                        tally.caught.push(prey.entity);
+                    }
+                }
+            }
+
+            // -------------------- Long Range Attraction --------------------
+            if universe.long_range {
+                let now = instant::Instant::now();
+                let attraction = long_range_attraction(
+                    boid_index.tree(),
+                    &query_region,
+                    transform.translation,
+                    universe.long_range_theta,
+                );
+                tally.long_range_time += now.elapsed().as_nanos();
+
+                if attraction.length() > 0.0 {
+                    direction += attraction.normalize() * universe.long_range_strength;
+                }
+            }
+
+            // -------------------- Obstacle Avoidance --------------------
+            if !obstacle_index.is_empty() {
+                let avoid = obstacle_avoidance(
+                    universe,
+                    obstacle_index,
+                    obstacles,
+                    position,
+                    velocity.value,
+                );
+                if avoid.length() > 0.0 {
+                    direction += avoid.normalize() * universe.avoidance;
+                }
+            }
+
+            if universe.border == BorderMode::Steer {
+                direction += border_steering(universe, position);
+            }
+
+            let mut new_velocity = match universe.steering {
+                SteeringMode::Legacy => direction.normalize_or_zero() * velocity.value.length(),
+                SteeringMode::Reynolds => {
+                    let desired = direction.normalize_or_zero() * params.max_speed;
+                    acceleration.value =
+                        (desired - velocity.value).clamp_length_max(universe.max_force);
+                    velocity.value
+                }
+            };
+
+            // -------------------- World Border --------------------
+            if universe.border == BorderMode::Bounce {
+                let margin = universe.border_margin as i32;
+                if (x < win.min.x + margin && velocity.value.x < 0.0)
+                    || (x > win.max.x - margin && velocity.value.x > 0.0)
+                {
+                    new_velocity.x *= -1.0;
+                }
+                if (y < win.min.y + margin && velocity.value.y < 0.0)
+                    || (y > win.max.y - margin && velocity.value.y > 0.0)
+                {
+                    new_velocity.y *= -1.0;
+                }
+            }
+
+            // finally set the new velocity
+            velocity.value = new_velocity;
+
+            // -------------------- Flow Field --------------------
+            // pushes past `max_force`, the boids don't steer it
+            if flow.mode != FlowMode::Off {
+                acceleration.value += flow
+                    .sample(position.truncate(), world_min, world_max)
+                    .extend(0.0);
+            }
+        },
+    );
+
+    let mut query_time: u128 = 0;
+    let mut long_range_time: u128 = 0;
+    let mut caught: Vec<Entity> = Vec::new();
+    tallies.iter_mut().for_each(|tally| {
+        let tally = tally.get_mut();
+        query_time += std::mem::take(&mut tally.query_time);
+        long_range_time += std::mem::take(&mut tally.long_range_time);
+        caught.append(&mut tally.caught);
+    });
+
+    // prey caught by several predators goes once, in entity order so the
+    // run stays the same however the threads split the work
+    caught.sort_unstable();
+    caught.dedup();
+    caught.iter().for_each(|prey| {
+        commands.entity(*prey).despawn_recursive();
+    });
+    caught_prey.0 = caught;
+
+    let boid_count = query.iter().len().max(1) as u128;
+    bench.avarage_build_time = boid_index.sync_time();
+    bench.avarage_query_time = query_time / boid_count;
+    bench.avarage_long_range_time = long_range_time / boid_count;
+}
+
+/// where a boid looks for neighbours, as far as the widest steering rule reaches
+pub(crate) fn vision_region(
+    universe: &BoidUniverse,
+    collider: &Collider,
+    position: Vec3,
+) -> Region {
+    let reach = match universe.steering {
+        SteeringMode::Legacy => universe.vision * 10.0,
+        SteeringMode::Reynolds => universe.max_radius(),
+    };
+    collider.into_region(position).with_margin(reach as i32)
+}
+
+/// pushes inwards, growing from nothing at `border_margin` off the edge
+/// to `border_force` on it
+pub(crate) fn border_steering(universe: &BoidUniverse, position: Vec3) -> Vec3 {
+    let (min_x, min_y, max_x, max_y) = universe.bounds.into_f32();
+    let margin = universe.border_margin.max(1.0);
+    let push = |min: f32, max: f32, at: f32| {
+        let near_min = (1.0 - (at - min) / margin).clamp(0.0, 1.0);
+        let near_max = (1.0 - (max - at) / margin).clamp(0.0, 1.0);
+        near_min - near_max
+    };
+
+    Vec3::new(
+        push(min_x, max_x, position.x),
+        push(min_y, max_y, position.y),
+        0.0,
+    ) * universe.border_force
+}
+
+/// the original steering, kept for comparison. Sums unit vectors from the
+/// world origin for cohesion and weights every neighbour equally.
+fn legacy_steering(params: &SpeciesParams, neighbours: &[&Body], position: Vec3) -> Vec3 {
+    let (mass_center, aligment, separtion) = neighbours.iter().fold(
+        (Vec3::ZERO, Vec3::ZERO, Vec3::ZERO),
+        |(mcen, alg, sep), body| {
+            (
+                mcen + body.position.normalize(),
+                alg + body.velocity.normalize_or_zero(),
+                sep + (position - body.position).normalize(),
+            )
+        },
+    );
+
+    let mut steering = Vec3::ZERO;
+
+    // -------------------- Cohesion --------------------
+    if mass_center.length() > 0.0 {
+        steering += (mass_center.normalize() - position.normalize()).normalize() * params.cohesion;
+    }
+
+    // -------------------- Alignment --------------------
+    if aligment.length() > 0.0 {
+        steering += aligment.normalize() * params.alignment;
+    }
+
+    // -------------------- Separation --------------------
+    if separtion.length() > 0.0 {
+        steering += separtion.normalize() * params.separation;
+    }
+
+    steering
+}
+
+/// Reynolds rules, each with its own radius: steer to the centroid,
+/// match the mean velocity and push away from close neighbours by 1 / distance
+fn reynolds_steering(
+    universe: &BoidUniverse,
+    params: &SpeciesParams,
+    neighbours: &[&Body],
+    position: Vec3,
+) -> Vec3 {
+    let mut centroid = (Vec3::ZERO, 0);
+    let mut heading = (Vec3::ZERO, 0);
+    let mut separation = Vec3::ZERO;
+
+    neighbours.iter().for_each(|body| {
+        let offset = position - body.position;
+        let distance = offset.length();
+
+        if distance < universe.cohesion_radius {
+            centroid = (centroid.0 + body.position, centroid.1 + 1);
+        }
+        if distance < universe.alignment_radius {
+            heading = (heading.0 + body.velocity, heading.1 + 1);
+        }
+        if distance < universe.separation_radius && distance > 0.0 {
+            separation += offset / (distance * distance);
+        }
+    });
+
+    let mut steering = Vec3::ZERO;
+
+    // -------------------- Cohesion --------------------
+    if centroid.1 > 0 {
+        let to_center = centroid.0 / centroid.1 as f32 - position;
+        steering += to_center.normalize_or_zero() * params.cohesion;
+    }
+
+    // -------------------- Alignment --------------------
+    if heading.1 > 0 {
+        steering += (heading.0 / heading.1 as f32).normalize_or_zero() * params.alignment;
+    }
+
+    // -------------------- Separation --------------------
+    if separation.length() > 0.0 {
+        steering += separation.normalize() * params.separation;
+    }
+
+    steering
+}
+
+/// pushes away from obstacles found along the way ahead, closer hits push harder
+fn obstacle_avoidance(
+    universe: &BoidUniverse,
+    obstacle_index: &SpatialQuery<Obstacle>,
+    obstacles: &Query<(&Transform, &Obstacle)>,
+    position: Vec3,
+    velocity: Vec3,
+) -> Vec3 {
+    let position = position.truncate();
+    let ahead = position + velocity.truncate().normalize_or_zero() * universe.avoidance_distance;
+    let clearance = BOID_SIZE * 2.0;
+    let (min, max) = (position.min(ahead), position.max(ahead));
+    let look_region = Region::new(Coord::from_f32(min.x, min.y), Coord::from_f32(max.x, max.y))
+        .with_margin(clearance as i32);
+
+    const SAMPLES: usize = 4;
+    let mut push = Vec2::ZERO;
+    obstacle_index
+        .query(&look_region, &[])
+        .into_iter()
+        .filter_map(|entity| obstacles.get(*entity).ok())
+        .for_each(|(transform, obstacle)| {
+            let center = transform.translation.truncate();
+            // first sample along the way ahead that gets too close
+            let hit = (0..=SAMPLES).find_map(|step| {
+                let t = step as f32 / SAMPLES as f32;
+                let sample = position.lerp(ahead, t);
+                let closest = obstacle.closest_point(center, sample);
+                (sample.distance(closest) < clearance).then_some((t, sample, closest))
+            });
+
+            if let Some((t, sample, closest)) = hit {
+                let away = match (sample - closest).try_normalize() {
+                    Some(away) => away,
+                    None => (sample - center).normalize_or_zero(),
+                };
+                push += away * (1.0 - t * 0.5);
+            }
+        });
+
+    push.extend(0.0)
+}
+
+/// Barnes-Hut style pull towards distant flocks. Nodes that look small enough
+/// from `position` (size / distance < theta) act as a single mass in their centroid,
+/// everything inside the vision region is left to the local rules.
+pub(crate) fn long_range_attraction(
+    graph: &QuadTree<Body, FlockAggregate>,
+    vision: &Region,
+    position: Vec3,
+    theta: f32,
+) -> Vec3 {
+    let mut force = Vec3::ZERO;
+    graph.visit(|region, flock, is_leaf| {
+        let Some(centroid) = flock.centroid() else {
+            return false;
+        };
+
+        if region.intersects(vision) {
+            return true;
+        }
+
+        let offset = centroid - position;
+        let distance = offset.length();
+        let (width, _) = region.size_f32();
+
+        if is_leaf || width / distance < theta {
+            if distance > 0.0 {
+                force += offset / distance * flock.count as f32 / (distance * distance);
+            }
+            return false;
+        }
+        true
+    });
+    force
+}
+
+pub fn move_system(
+    mut query: Query<(
+        &mut Transform,
+        &mut Velocity,
+        &mut Acceleration,
+        &Species,
+        Option<&Genome>,
+    )>,
+    universe: Res<BoidUniverse>,
+    flow: Res<FlowField>,
+    time: Res<Time>,
+) {
+    let delta = time.delta_seconds() * universe.speed;
+    let flowing = flow.mode != FlowMode::Off;
+    query.par_iter_mut().for_each(
+        |(mut transform, mut velocity, mut acceleration, species, genome)| {
+            let max_speed = universe.params(*species).max_speed
+                * genome.map_or(1.0, |genome| genome.max_speed);
+            match universe.steering {
+                SteeringMode::Reynolds => {
+                    velocity.value = integrate_velocity(
+                        &universe,
+                        max_speed,
+                        velocity.value,
+                        acceleration.value,
+                        delta,
+                    );
+                }
+                // a flow keeps pushing while the steering keeps the speed,
+                // it would add up forever without the limit
+                SteeringMode::Legacy => {
+                    velocity.value += acceleration.value * delta;
+                    if flowing {
+                        velocity.value = velocity.value.clamp_length_max(max_speed);
+                    }
+                }
+            }
+            acceleration.value = Vec3::ZERO;
+
+            // a boid brought to a halt keeps facing where it went
+            if let Some(direction) = velocity.value.try_normalize() {
+                let rotation = Quat::from_rotation_z(-direction.x.atan2(direction.y) + PI / 2.0);
+                transform.rotation = rotation;
+            }
+            transform.translation += velocity.value * delta;
+
+            if universe.border == BorderMode::Wrap {
+                wrap_position(&universe, &mut transform.translation);
+            }
+        },
+    );
+}
+
+/// runs after `move_system`, one boid after the other so the draws from
+/// `SimRng` happen in entity order
+pub fn respawn_escaped(
+    mut query: Query<(&mut Transform, &mut Velocity), With<Boid>>,
+    universe: Res<BoidUniverse>,
+    mut rng: ResMut<SimRng>,
+) {
+    if universe.border != BorderMode::Respawn {
+        return;
+    }
+
+    query.iter_mut().for_each(|(mut transform, mut velocity)| {
+        respawn_outside(
+            &universe,
+            &mut rng,
+            &mut transform.translation,
+            &mut velocity.value,
+        );
+    });
+}
+
+pub(crate) fn wrap_position(universe: &BoidUniverse, position: &mut Vec3) {
+    let (min_x, min_y, max_x, max_y) = universe.bounds.into_f32();
+    position.x = min_x + (position.x - min_x).rem_euclid(max_x - min_x);
+    position.y = min_y + (position.y - min_y).rem_euclid(max_y - min_y);
+}
+
+/// a boid leaving the world starts over at a random position, heading
+/// somewhere random at the same speed. It is moved rather than replaced,
+/// keeping its entity and genes
+pub(crate) fn respawn_outside(
+    universe: &BoidUniverse,
+    rng: &mut SimRng,
+    position: &mut Vec3,
+    velocity: &mut Vec3,
+) {
+    let (min_x, min_y, max_x, max_y) = universe.bounds.into_f32();
+    if (min_x..=max_x).contains(&position.x) && (min_y..=max_y).contains(&position.y) {
+        return;
+    }
+
+    position.x = min_x + rng.gen::<f32>() * (max_x - min_x);
+    position.y = min_y + rng.gen::<f32>() * (max_y - min_y);
+    let heading = rng.gen::<f32>() * TAU;
+    *velocity = Vec2::from(heading.sin_cos()).extend(0.0) * velocity.length();
+}
+
+/// applies the steering force and drag, then limits the turn and speed
+pub(crate) fn integrate_velocity(
+    universe: &BoidUniverse,
+    max_speed: f32,
+    velocity: Vec3,
+    force: Vec3,
+    delta: f32,
+) -> Vec3 {
+    let mut new_velocity = velocity + force * delta;
+    new_velocity *= (1.0 - universe.drag * delta).max(0.0);
+
+    // -------------------- Turn Rate --------------------
+    let heading = velocity.y.atan2(velocity.x);
+    let turn = (new_velocity.y.atan2(new_velocity.x) - heading + PI).rem_euclid(TAU) - PI;
+    let max_turn = universe.max_turn_rate.to_radians() * delta;
+    if turn.abs() > max_turn {
+        let (sin, cos) = (heading + max_turn.copysign(turn)).sin_cos();
+        new_velocity = Vec3::new(cos, sin, 0.0) * new_velocity.length();
+    }
+
+    // -------------------- Speed Limits --------------------
+    if new_velocity.length() == 0.0 {
+        new_velocity = velocity.normalize_or_zero() * universe.min_speed;
+    }
+    let min_speed = universe.min_speed.min(max_speed);
+    new_velocity.clamp_length(min_speed, max_speed)
+}
+
+pub fn advance_tick(mut tick: ResMut<SimTick>) {
+    tick.0 += 1;
+}
+
+pub fn count_boids(query: Query<&Boid>, mut universe: ResMut<BoidUniverse>) {
+    universe.boid_count = query.iter().count() as u32;
+}
+
+type CursorBoids<'w, 's> =
+    Query<'w, 's, (&'static Transform, &'static mut Velocity), (With<Boid>, Without<Cursor>)>;
+
+/// where the mouse points and which buttons it holds
+#[derive(SystemParam)]
+pub struct Pointer<'w, 's> {
+    window: Query<'w, 's, &'static Window>,
+    camera: Query<'w, 's, (&'static Camera, &'static GlobalTransform)>,
+    cursor_quad: Query<'w, 's, &'static mut Transform, With<Cursor>>,
+    buttons: Res<'w, Input<MouseButton>>,
+}
+
+/// what the mouse tools spawn from and look up to remove
+#[derive(SystemParam)]
+pub struct MouseTools<'w> {
+    boid_assets: Res<'w, BoidAssets>,
+    rng: ResMut<'w, SimRng>,
+    boid_index: SpatialQuery<'w, Boid>,
+    obstacle_index: SpatialQuery<'w, Obstacle>,
+}
+
+pub fn handle_mouse(
+    mut commands: Commands,
+    mut pointer: Pointer,
+    mut boids: CursorBoids,
+    mut tools: MouseTools,
+    universe: ResMut<BoidUniverse>,
+    mut selected: ResMut<SelectedBoid>,
+    time: Res<Time>,
+) {
+    let Some(cursor_pos_win) = pointer.window.single().cursor_position() else {
+        return;
+    };
+
+    if universe.mouse_used_by_egui {
+        return;
+    }
+
+    let (camera, camera_transform) = pointer.camera.single();
+    let mut cursor_quad_transform = pointer.cursor_quad.single_mut();
+    let buttons = &pointer.buttons;
+    let MouseTools {
+        boid_assets,
+        rng,
+        boid_index,
+        obstacle_index,
+    } = &mut tools;
+
+    if let Some(pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos_win) {
+        cursor_quad_transform.translation = Vec3::new(pos.x, pos.y, 0.0);
+        // the quad shows the reach of the force tools
+        cursor_quad_transform.scale = match universe.mouse_tool.is_force() {
+            true => Vec3::splat(universe.cursor_radius * 2.0 / CURSOR_QUAD_SIZE),
+            false => Vec3::ONE,
+        };
+
+        match universe.mouse_tool {
+            MouseTool::Boids => {
+                if buttons.just_pressed(MouseButton::Left) {
+                    let species = universe.spawn_species;
+                    self::spawn_boids(
+                        &mut commands,
+                        boid_assets,
+                        rng,
+                        pos,
+                        species,
+                        universe.params(species),
+                    );
+                }
+
+                if buttons.just_pressed(MouseButton::Right) {
+                    self::despawn_boids(&mut commands, pos, boid_index);
+                }
+            }
+            MouseTool::Obstacles => {
+                if buttons.just_pressed(MouseButton::Left) {
+                    commands.spawn((
+                        SpatialBundle::from_transform(Transform::from_xyz(pos.x, pos.y, 0.0)),
+                        universe.obstacle,
+                        Collider::new(universe.obstacle.bounding_size()),
+                    ));
+                }
+
+                if buttons.just_pressed(MouseButton::Right) {
+                    obstacle_index
+                        .query(&cursor_region(pos), &[])
+                        .into_iter()
+                        .for_each(|entity| commands.entity(*entity).despawn_recursive());
+                }
+            }
+            MouseTool::Attract | MouseTool::Repel | MouseTool::Vortex => {
+                if buttons.pressed(MouseButton::Left) {
+                    let delta = time.delta_seconds() * universe.speed;
+                    self::apply_cursor_force(&universe, boid_index, pos, &mut boids, delta);
+                }
+            }
+        }
+
+        if buttons.just_pressed(MouseButton::Middle) {
+            selected.0 = boid_index
+                .query_nearest(&cursor_region(pos), &[], Some(1))
+                .first()
+                .map(|hit| hit.value.entity);
+        }
+    }
+}
+
+fn spawn_boids(
+    commands: &mut Commands,
+    assets: &BoidAssets,
+    rng: &mut SimRng,
+    position: Vec2,
+    species: Species,
+    params: &SpeciesParams,
+) {
+    for _ in 0..params.spawn_count {
+        let x = position.x + (rng.gen::<f32>() - 0.5) * (CURSOR_QUAD_SIZE / 2.0);
+        let y = position.y + (rng.gen::<f32>() - 0.5) * (CURSOR_QUAD_SIZE / 2.0);
+
+        let initial_speed = 200.0 + rng.gen::<f32>() * 200.0;
+        let velocity = Vec3::new(
+            (rng.gen::<f32>() - 0.5) * initial_speed,
+            (rng.gen::<f32>() - 0.5) * initial_speed,
+            0.0,
+        );
+
+        spawn_boid(commands, assets, species, Vec3::new(x, y, 0.0), velocity);
+    }
+}
+
+pub fn spawn_boid(
+    commands: &mut Commands,
+    assets: &BoidAssets,
+    species: Species,
+    position: Vec3,
+    velocity: Vec3,
+) -> Entity {
+    commands
+        .spawn(MaterialMesh2dBundle {
+            // texture: assets.load("boid.png"),
+            mesh: assets.mesh.clone(),
+            material: assets.species(species),
+            // texture: assets.load("/files/assets/boid.png"),
+            transform: Transform::from_translation(position),
+            ..Default::default()
+        })
+        .insert(Boid)
+        .insert(species)
+        .insert(Velocity { value: velocity })
+        .insert(Acceleration::default())
+        .insert(Collider::new(BOID_SIZE))
+        .id()
+}
+
+fn despawn_boids(commands: &mut Commands, position: Vec2, boid_index: &SpatialIndex<Boid>) {
+    boid_index
+        .query(&cursor_region(position), &[])
+        .iter()
+        .for_each(|body| commands.entity(body.entity).despawn_recursive());
+}
+
+fn cursor_region(position: Vec2) -> Region {
+    square_region(position, CURSOR_QUAD_SIZE / 2.0)
+}
+
+fn square_region(position: Vec2, half_size: f32) -> Region {
+    Region::new(
+        Coord::from_f32(position.x - half_size, position.y - half_size),
+        Coord::from_f32(position.x + half_size, position.y + half_size),
+    )
+}
+
+/// pushes the velocity of every boid in reach of the cursor, strongest at
+/// the center and fading out linearly towards `cursor_radius`
+fn apply_cursor_force(
+    universe: &BoidUniverse,
+    boid_index: &SpatialIndex<Boid>,
+    position: Vec2,
+    boids: &mut CursorBoids,
+    delta: f32,
+) {
+    let radius = universe.cursor_radius;
+    boid_index
+        .query(&square_region(position, radius), &[])
+        .iter()
+        .for_each(|body| {
+            let Ok((transform, mut velocity)) = boids.get_mut(body.entity) else {
+                return;
+            };
+
+            let offset = transform.translation.truncate() - position;
+            let distance = offset.length();
+            if distance >= radius {
+                return;
+            }
+
+            let outwards = offset.normalize_or_zero();
+            let direction = match universe.mouse_tool {
+                MouseTool::Attract => -outwards,
+                MouseTool::Repel => outwards,
+                MouseTool::Vortex => outwards.perp(),
+                MouseTool::Boids | MouseTool::Obstacles => return,
+            };
+
+            let falloff = 1.0 - distance / radius;
+            velocity.value += (direction * universe.cursor_strength * falloff * delta).extend(0.0);
+        });
+}
@@ -0,0 +1,775 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    analytics::measure,
+    components::{Body, Boid, Collider, Obstacle, Perception, Trail, Velocity},
+    ecology::{Ecology, Energy, Genome},
+    flow::FlowGrid,
+    headless::{self, HeadlessConfig},
+    presets::{Preset, Presets},
+    replay::{BoidState, Recorder, Replay, ReplayEvent, ReplaySettings},
+    resources::{BoidUniverse, BorderMode, ColorMode, MouseTool, SimRng, SteeringMode},
+    snapshot::Snapshot,
+    spatial::SpatialIndex,
+    species::{Species, SpeciesParams, SpeciesRule},
+    systems::{
+        border_steering, integrate_velocity, long_range_attraction, respawn_outside, wrap_position,
+    },
+    MAX_TRAIL_LEN,
+};
+
+/// a file in the temp dir, unique to this test process
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("boids-{}-{}", std::process::id(), name))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// runs headless and returns the saved scene
+fn run_to_scene(config: HeadlessConfig, name: &str) -> String {
+    let path = temp_path(name);
+    headless::run(&HeadlessConfig {
+        save: Some(path.clone()),
+        ..config
+    })
+    .unwrap();
+    let scene = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    scene
+}
+
+#[test]
+fn same_seed_same_run() {
+    let config = HeadlessConfig {
+        boids: 500,
+        ticks: 200,
+        seed: 3,
+        ..Default::default()
+    };
+
+    // the parallel steering pass must not change anything between runs
+    let first = run_to_scene(config.clone(), "first.ron");
+    let second = run_to_scene(config, "second.ron");
+    assert_eq!(first, second);
+}
+
+#[test]
+fn parse_params() {
+    let text = "
+        # tuned for a tight flock
+        prey.cohesion = 0.2
+        max_force=800 # per second
+    ";
+    assert_eq!(
+        headless::parse_params(text).unwrap(),
+        vec![
+            ("prey.cohesion".to_string(), 0.2),
+            ("max_force".to_string(), 800.0),
+        ]
+    );
+    assert!(headless::parse_params("").unwrap().is_empty());
+
+    let err = headless::parse_params("drag = 0.1\ndrag 0.2").unwrap_err();
+    assert!(err.starts_with("line 2"), "{}", err);
+    let err = headless::parse_params("drag = slow").unwrap_err();
+    assert!(err.starts_with("line 1"), "{}", err);
+}
+
+#[test]
+fn headless_rejects_tiny_worlds() {
+    [
+        Vec2::new(1.0, 100.0),
+        Vec2::new(100.0, 1.9),
+        Vec2::ZERO,
+        Vec2::NAN,
+    ]
+    .iter()
+    .for_each(|size| {
+        let err = headless::run(&HeadlessConfig {
+            boids: 10,
+            ticks: 1,
+            world_size: *size,
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(err.starts_with("world size"), "{}", err);
+    });
+
+    let report = headless::run(&HeadlessConfig {
+        boids: 10,
+        ticks: 1,
+        world_size: Vec2::splat(2.0),
+        ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(report.boids, 10);
+}
+
+fn boid_state(entity: u64, x: f32, species: Species) -> BoidState {
+    BoidState {
+        entity,
+        position: Vec2::new(x, -x),
+        velocity: Vec2::new(1.5, 2.5),
+        species,
+    }
+}
+
+/// two ticks, changing a param, the settings and the boids in between
+fn recording() -> Vec<u8> {
+    let mut universe = BoidUniverse::new(Vec2::splat(-100.0), Vec2::splat(100.0));
+    let mut recorder = Recorder::new(Vec::new()).unwrap();
+    let first = [
+        boid_state(1, 10.0, Species::Prey),
+        boid_state(2, 20.0, Species::Predator),
+    ];
+    recorder.write_tick(&universe, 1, &first).unwrap();
+
+    universe.drag = 0.5;
+    universe.steering = SteeringMode::Legacy;
+    universe.border = BorderMode::Wrap;
+    universe.long_range = true;
+    universe.rules[Species::Prey.index()][Species::Predator.index()] = SpeciesRule::Ignore;
+    let second = [
+        boid_state(2, 30.0, Species::Predator),
+        boid_state(3, 40.0, Species::Prey),
+    ];
+    recorder.write_tick(&universe, 2, &second).unwrap();
+    recorder.finish().unwrap()
+}
+
+#[test]
+fn replay_round_trip() {
+    let replay = Replay::decode(recording()).unwrap();
+    assert_eq!(replay.params, BoidUniverse::PARAM_NAMES);
+    assert_eq!(replay.frame_count(), 2);
+    assert_eq!((replay.tick(1), replay.boid_count(1)), (2, 2));
+
+    let (first, second) = (&replay.frame(0), &replay.frame(1));
+    assert_eq!(first.tick, 1);
+    assert_eq!(first.boids[1], boid_state(2, 20.0, Species::Predator));
+    // every param and the settings once, then both spawns
+    let params = first
+        .events
+        .iter()
+        .filter(|event| matches!(event, ReplayEvent::Param(..)))
+        .count();
+    assert_eq!(params, BoidUniverse::PARAM_NAMES.len());
+    assert!(first.events.contains(&ReplayEvent::Spawn(2)));
+
+    let drag = BoidUniverse::PARAM_NAMES
+        .iter()
+        .position(|name| *name == "drag")
+        .unwrap() as u16;
+    let mut rules = BoidUniverse::new(Vec2::ZERO, Vec2::ONE).rules;
+    rules[Species::Prey.index()][Species::Predator.index()] = SpeciesRule::Ignore;
+    assert_eq!(second.tick, 2);
+    assert_eq!(
+        second.boids,
+        vec![
+            boid_state(2, 30.0, Species::Predator),
+            boid_state(3, 40.0, Species::Prey)
+        ]
+    );
+    assert_eq!(
+        second.events,
+        vec![
+            ReplayEvent::Param(drag, 0.5),
+            ReplayEvent::Settings(ReplaySettings {
+                steering: SteeringMode::Legacy,
+                border: BorderMode::Wrap,
+                long_range: true,
+                rules,
+            }),
+            ReplayEvent::Spawn(3),
+            ReplayEvent::Despawn(1),
+        ]
+    );
+}
+
+#[test]
+fn replay_scrubs_past_checkpoints() {
+    let mut universe = BoidUniverse::new(Vec2::splat(-100.0), Vec2::splat(100.0));
+    let mut recorder = Recorder::new(Vec::new()).unwrap();
+    for tick in 0..600 {
+        universe.drag = tick as f32;
+        universe.long_range = tick % 2 == 1;
+        recorder.write_tick(&universe, tick, &[]).unwrap();
+    }
+    let replay = Replay::decode(recorder.finish().unwrap()).unwrap();
+
+    [0, 1, 255, 256, 257, 599, 300, 10]
+        .iter()
+        .for_each(|&index| {
+            let drag = replay
+                .params_at(index)
+                .into_iter()
+                .find(|(name, _)| *name == "drag")
+                .map(|(_, value)| value);
+            assert_eq!(drag, Some(index as f32));
+            let settings = replay.settings_at(index).unwrap();
+            assert_eq!(settings.long_range, index % 2 == 1);
+        });
+}
+
+fn decode_err(bytes: &[u8]) -> String {
+    match Replay::decode(bytes) {
+        Ok(_) => panic!("decoded a broken recording"),
+        Err(err) => err,
+    }
+}
+
+#[test]
+fn replay_rejects_broken_files() {
+    let bytes = recording();
+
+    let err = decode_err(&bytes[..bytes.len() - 3]);
+    assert_eq!(err, "recording ends early");
+    let header = Recorder::new(Vec::new()).unwrap().finish().unwrap();
+    assert_eq!(decode_err(&header), "recording holds no frames");
+    assert!(Replay::decode(b"not a recording").is_err());
+
+    let mut bad_tag = bytes.clone();
+    let last_frame = bytes.len() - (1 + 8 + 4 + 2 * 25);
+    bad_tag[last_frame] = 99;
+    let err = decode_err(&bad_tag);
+    assert!(err.starts_with("unknown record 99"), "{}", err);
+}
+
+#[test]
+fn snapshot_round_trip() {
+    let mut universe = BoidUniverse::new(Vec2::new(-300.0, -200.0), Vec2::new(300.0, 200.0));
+    universe.species[Species::Prey.index()].cohesion = 0.25;
+    universe.border = BorderMode::Steer;
+    universe.long_range = true;
+    universe.rules[Species::Predator.index()][Species::Prey.index()] = SpeciesRule::Ignore;
+
+    let boids = [
+        (
+            Transform::from_xyz(10.0, 20.0, 0.0).with_rotation(Quat::from_rotation_z(1.0)),
+            Velocity {
+                value: Vec3::new(3.0, -4.0, 0.0),
+            },
+            Collider::new(5.0),
+            Species::Prey,
+            Some(Energy(42.0)),
+            Some(Genome {
+                max_speed: 1.25,
+                ..Default::default()
+            }),
+        ),
+        (
+            Transform::from_xyz(-50.0, 0.5, 0.0),
+            Velocity { value: Vec3::X },
+            Collider::new(7.5),
+            Species::Predator,
+            None,
+            None,
+        ),
+    ];
+    let obstacles = [(
+        Transform::from_xyz(0.0, 0.0, 0.0),
+        Obstacle::Rect {
+            size: Vec2::new(40.0, 10.0),
+        },
+    )];
+    let snapshot = Snapshot::capture(
+        &universe,
+        boids
+            .iter()
+            .map(|(t, v, c, s, e, g)| (t, v, c, s, e.as_ref(), g.as_ref())),
+        obstacles.iter().map(|(t, o)| (t, o)),
+    );
+
+    let path = temp_path("snapshot.ron");
+    snapshot.save(&path).unwrap();
+    let loaded = Snapshot::load(&path);
+    let _ = std::fs::remove_file(&path);
+    let loaded = loaded.unwrap();
+    assert_eq!(loaded, snapshot);
+    assert_eq!(loaded.boids[0].energy, Some(42.0));
+
+    // scenes saved before boids had energy and genes still load
+    let text = ron::ser::to_string(&snapshot).unwrap();
+    let text = text
+        .replace(",energy:Some(42.0)", "")
+        .replace(",energy:None", "")
+        .replace(",genome:None", "");
+    let text = text.replacen(
+        &format!(
+            ",genome:Some({})",
+            ron::ser::to_string(&snapshot.boids[0].genome.unwrap()).unwrap()
+        ),
+        "",
+        1,
+    );
+    assert!(
+        !text.contains("energy") && !text.contains("genome"),
+        "{}",
+        text
+    );
+    let old: Snapshot = ron::from_str(&text).unwrap();
+    assert_eq!(old.boids[0].energy, None);
+    assert_eq!(old.boids[0].genome, None);
+
+    let restored = loaded.universe();
+    assert_eq!(restored.bounds.into_f32(), universe.bounds.into_f32());
+    assert_eq!(restored.param("prey.cohesion"), Some(0.25));
+    assert_eq!(restored.border, BorderMode::Steer);
+    assert!(restored.long_range);
+    assert_eq!(restored.rules, universe.rules);
+
+    // loading into a running window keeps what only the window shows
+    let mut running = BoidUniverse::new(Vec2::splat(-50.0), Vec2::splat(50.0));
+    running.color_mode = ColorMode::Heading;
+    running.show_trails = true;
+    running.mouse_tool = MouseTool::Vortex;
+    loaded.apply(&mut running);
+    assert_eq!(running.bounds.into_f32(), universe.bounds.into_f32());
+    assert_eq!(running.param("prey.cohesion"), Some(0.25));
+    assert_eq!(running.border, BorderMode::Steer);
+    assert_eq!(running.color_mode, ColorMode::Heading);
+    assert!(running.show_trails);
+    assert_eq!(running.mouse_tool, MouseTool::Vortex);
+}
+
+#[test]
+fn lone_boids_are_no_flock() {
+    let universe = BoidUniverse::new(Vec2::splat(-400.0), Vec2::splat(400.0));
+    let mut boid_index = SpatialIndex::<Boid>::new(universe.bounds.clone());
+    // a pair, a chain of three and one boid far from everyone
+    let positions = [
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(10.0, 0.0, 0.0),
+        Vec3::new(-200.0, -100.0, 0.0),
+        Vec3::new(-180.0, -100.0, 0.0),
+        Vec3::new(-160.0, -100.0, 0.0),
+        Vec3::new(250.0, 250.0, 0.0),
+    ];
+    let boids: Vec<_> = positions
+        .iter()
+        .enumerate()
+        .map(|(index, position)| {
+            let entity = Entity::from_raw(index as u32);
+            let collider = Collider::new(5.0);
+            boid_index.insert(
+                entity,
+                collider.into_region(*position),
+                Body {
+                    entity,
+                    species: Species::Prey,
+                    position: *position,
+                    velocity: Vec3::X,
+                },
+            );
+            let velocity = Velocity { value: Vec3::X };
+            (
+                entity,
+                Transform::from_translation(*position),
+                velocity,
+                collider,
+            )
+        })
+        .collect();
+
+    let stats = measure(
+        &universe,
+        &boid_index,
+        0,
+        boids.iter().map(|(e, t, v, c)| (*e, t, v, c)),
+    );
+    assert_eq!(stats.boids, 6);
+    assert_eq!(stats.flocks, 2);
+}
+
+#[test]
+fn flow_grid_from_csv() {
+    let grid = FlowGrid::from_csv("1, 0, 0, 1\n\n-1,0,0,-1\n").unwrap();
+    assert_eq!((grid.columns, grid.rows), (2, 2));
+    assert_eq!(
+        grid.vectors,
+        vec![Vec2::X, Vec2::Y, Vec2::NEG_X, Vec2::NEG_Y]
+    );
+
+    [
+        ("", "no vectors"),
+        ("1,0\n1,0,0,1", "rows differ in length"),
+        ("1,0,1", "line 1: expected dx,dy pairs"),
+        ("1,0\nup,0", "line 2: expected numbers"),
+    ]
+    .iter()
+    .for_each(|(text, expected)| {
+        assert_eq!(FlowGrid::from_csv(text).unwrap_err(), *expected);
+    });
+}
+
+#[test]
+fn flow_grid_sample() {
+    // top row first, so the top left corner is `uv` 0, 1
+    let grid = FlowGrid::from_csv("1,0,0,1\n-1,0,0,-1").unwrap();
+    assert_eq!(grid.sample(Vec2::new(0.0, 1.0)), Vec2::X);
+    assert_eq!(grid.sample(Vec2::new(1.0, 1.0)), Vec2::Y);
+    assert_eq!(grid.sample(Vec2::new(0.0, 0.0)), Vec2::NEG_X);
+    assert_eq!(grid.sample(Vec2::new(1.0, 0.0)), Vec2::NEG_Y);
+    assert_eq!(grid.sample(Vec2::splat(0.5)), Vec2::ZERO);
+    assert_eq!(grid.sample(Vec2::new(0.5, 1.0)), Vec2::new(0.5, 0.5));
+    // outside the world sticks to the edge
+    assert_eq!(grid.sample(Vec2::new(-3.0, 7.0)), Vec2::X);
+
+    // a single vector covers everything
+    let grid = FlowGrid::from_csv("0.5,-0.5").unwrap();
+    assert_eq!(grid.sample(Vec2::splat(0.3)), Vec2::new(0.5, -0.5));
+}
+
+#[test]
+fn ecology_with_predators() {
+    let scene = run_to_scene(
+        HeadlessConfig {
+            boids: 500,
+            ticks: 1,
+            ..Default::default()
+        },
+        "prey.ron",
+    );
+    let mut scene: Snapshot = ron::from_str(&scene).unwrap();
+    scene
+        .boids
+        .iter_mut()
+        .step_by(5)
+        .for_each(|boid| boid.species = Species::Predator);
+
+    let prey_left = |scene: Snapshot, name: &str| {
+        let scene = run_to_scene(
+            HeadlessConfig {
+                ticks: 300,
+                scene: Some(scene),
+                ecology: true,
+                ..Default::default()
+            },
+            name,
+        );
+        let scene: Snapshot = ron::from_str(&scene).unwrap();
+        scene
+            .boids
+            .iter()
+            .filter(|boid| boid.species == Species::Prey)
+            .count()
+    };
+
+    // the same prey alone, so starving alone doesn't pass
+    let mut alone = scene.clone();
+    alone.boids.retain(|boid| boid.species == Species::Prey);
+    let hunted = prey_left(scene, "hunted.ron");
+    let unhunted = prey_left(alone, "unhunted.ron");
+    assert!(hunted < unhunted, "{} hunted, {} alone", hunted, unhunted);
+}
+
+#[test]
+fn genome_mutates_within_bounds() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let parent = Genome {
+        separation: 0.0,
+        cohesion: 2.0,
+        alignment: 1.0,
+        max_speed: 0.5,
+    };
+    for _ in 0..200 {
+        let child = parent.mutate(&mut rng, 0.3);
+        let genes = [
+            (parent.separation, child.separation),
+            (parent.cohesion, child.cohesion),
+            (parent.alignment, child.alignment),
+            (parent.max_speed, child.max_speed),
+        ];
+        genes.iter().for_each(|&(parent, child)| {
+            assert!(child >= 0.0);
+            assert!((child - parent).abs() <= parent * 0.3 + 1e-6);
+        });
+    }
+
+    // a mutation of more than 100% would flip genes negative
+    let child = Genome::default().mutate(&mut rng, 5.0);
+    assert!(child.separation >= 0.0 && child.max_speed >= 0.0);
+}
+
+#[test]
+fn genome_scales_species_params() {
+    let params = SpeciesParams::prey();
+    let genome = Genome {
+        separation: 2.0,
+        cohesion: 0.5,
+        alignment: 0.0,
+        max_speed: 1.5,
+    };
+    let expressed = genome.express(&params);
+    assert_eq!(expressed.separation, params.separation * 2.0);
+    assert_eq!(expressed.cohesion, params.cohesion * 0.5);
+    assert_eq!(expressed.alignment, 0.0);
+    assert_eq!(expressed.max_speed, params.max_speed * 1.5);
+    assert_eq!(expressed.flee, params.flee);
+
+    let unbred = Genome::default().express(&params);
+    assert_eq!(unbred.separation, params.separation);
+    assert_eq!(unbred.max_speed, params.max_speed);
+}
+
+#[test]
+fn food_patches_clamp_at_the_edge() {
+    let mut ecology = Ecology::default();
+    // three columns and two rows, the last ones only partly in the world
+    ecology.fit(Vec2::new(100.0, 60.0));
+    assert_eq!(ecology.patch(Vec2::ZERO), 0);
+    assert_eq!(ecology.patch(Vec2::new(45.0, 0.0)), 1);
+    assert_eq!(ecology.patch(Vec2::new(99.0, 59.0)), 5);
+    assert_eq!(ecology.patch(Vec2::new(100.0, 60.0)), 5);
+    assert_eq!(ecology.patch(Vec2::new(1000.0, 10.0)), 2);
+    assert_eq!(ecology.patch(Vec2::new(-10.0, -10.0)), 0);
+}
+
+#[test]
+fn distant_flock_pulls_as_one() {
+    let universe = BoidUniverse::new(Vec2::splat(-500.0), Vec2::splat(500.0));
+    let mut boid_index = SpatialIndex::<Boid>::new(universe.bounds.clone());
+    let mut insert = |index: u32, position: Vec3| {
+        let entity = Entity::from_raw(index);
+        boid_index.insert(
+            entity,
+            Collider::new(5.0).into_region(position),
+            Body {
+                entity,
+                species: Species::Prey,
+                position,
+                velocity: Vec3::X,
+            },
+        );
+    };
+    let position = Vec3::new(-400.0, -400.0, 0.0);
+    insert(0, position);
+    for i in 0..10 {
+        insert(i + 1, Vec3::new(400.0 + i as f32, 400.0, 0.0));
+    }
+
+    let vision = Collider::new(5.0).into_region(position).with_margin(50);
+    let force = long_range_attraction(boid_index.tree(), &vision, position, 0.5);
+    // ten boids at their centroid, falling off with the squared distance
+    let offset = Vec3::new(404.5, 400.0, 0.0) - position;
+    let expected = offset.normalize() * 10.0 / offset.length_squared();
+    assert!(force.angle_between(expected) < 0.01, "{:?}", force);
+    assert!((force.length() / expected.length() - 1.0).abs() < 0.01);
+
+    // within sight the local rules take over
+    let vision = Collider::new(5.0).into_region(position).with_margin(1000);
+    let force = long_range_attraction(boid_index.tree(), &vision, position, 0.5);
+    assert_eq!(force, Vec3::ZERO);
+}
+
+/// `degrees` counterclockwise from `Vec3::X`
+fn direction(degrees: f32) -> Vec3 {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    Vec3::new(cos, sin, 0.0)
+}
+
+#[test]
+fn perception_cone_and_blind_spot() {
+    let cone = Perception {
+        vision_angle: 270.0,
+        blind_spot: 0.0,
+    };
+    assert!(cone.sees(Vec3::X, direction(0.0) * 10.0));
+    assert!(cone.sees(Vec3::X, direction(134.0) * 10.0));
+    assert!(cone.sees(Vec3::X, direction(-134.0) * 10.0));
+    assert!(!cone.sees(Vec3::X, direction(136.0) * 10.0));
+    assert!(!cone.sees(Vec3::X, direction(180.0) * 10.0));
+
+    let blind = Perception {
+        vision_angle: 360.0,
+        blind_spot: 60.0,
+    };
+    assert!(blind.sees(Vec3::X, direction(149.0)));
+    assert!(!blind.sees(Vec3::X, direction(151.0)));
+    assert!(!blind.sees(Vec3::X, direction(-151.0)));
+
+    // without a heading or an offset there is nothing to be blind to
+    assert!(cone.sees(Vec3::ZERO, direction(180.0)));
+    assert!(blind.sees(Vec3::X, Vec3::ZERO));
+}
+
+#[test]
+fn velocity_turns_and_speed_are_limited() {
+    let mut universe = BoidUniverse::new(Vec2::splat(-100.0), Vec2::splat(100.0));
+    universe.drag = 0.0;
+    universe.min_speed = 50.0;
+    universe.max_turn_rate = 90.0;
+    let velocity = Vec3::new(100.0, 0.0, 0.0);
+
+    // a hard pull sideways only turns 9 degrees in a tenth of a second
+    let turned = integrate_velocity(&universe, 200.0, velocity, Vec3::Y * 10_000.0, 0.1);
+    let angle = turned.y.atan2(turned.x).to_degrees();
+    assert!((angle - 9.0).abs() < 1e-3, "{}", angle);
+    assert!((turned.length() - 200.0).abs() < 1e-3);
+
+    // a small turn goes through as it is
+    let nudged = integrate_velocity(&universe, 200.0, velocity, Vec3::Y * 100.0, 0.1);
+    assert!((nudged - Vec3::new(100.0, 10.0, 0.0)).length() < 1e-3);
+
+    let pushed = integrate_velocity(&universe, 200.0, velocity, Vec3::X * 10_000.0, 0.1);
+    assert!((pushed - Vec3::new(200.0, 0.0, 0.0)).length() < 1e-3);
+    let slowed = integrate_velocity(&universe, 200.0, velocity, Vec3::X * -900.0, 0.1);
+    assert!((slowed - Vec3::new(50.0, 0.0, 0.0)).length() < 1e-3);
+
+    // a full stop keeps the heading at the minimum speed
+    let stopped = integrate_velocity(&universe, 200.0, velocity, Vec3::X * -1000.0, 0.1);
+    assert!((stopped - Vec3::new(50.0, 0.0, 0.0)).length() < 1e-3);
+
+    // a max speed below the minimum wins
+    let capped = integrate_velocity(&universe, 30.0, velocity, Vec3::ZERO, 0.1);
+    assert!((capped.length() - 30.0).abs() < 1e-3);
+}
+
+#[test]
+fn closest_point_on_obstacles() {
+    let center = Vec2::new(10.0, 20.0);
+
+    let circle = Obstacle::Circle { radius: 10.0 };
+    assert_eq!(
+        circle.closest_point(center, Vec2::new(40.0, 20.0)),
+        Vec2::new(20.0, 20.0)
+    );
+    let inside = Vec2::new(12.0, 17.0);
+    assert_eq!(circle.closest_point(center, inside), inside);
+
+    let rect = Obstacle::Rect {
+        size: Vec2::new(20.0, 10.0),
+    };
+    assert_eq!(
+        rect.closest_point(center, Vec2::new(50.0, 50.0)),
+        Vec2::new(20.0, 25.0)
+    );
+    assert_eq!(
+        rect.closest_point(center, Vec2::new(15.0, 0.0)),
+        Vec2::new(15.0, 15.0)
+    );
+    assert_eq!(rect.closest_point(center, inside), inside);
+
+    let segment = Obstacle::Segment {
+        half_extent: Vec2::new(10.0, 0.0),
+    };
+    assert_eq!(
+        segment.closest_point(center, Vec2::new(15.0, 27.0)),
+        Vec2::new(15.0, 20.0)
+    );
+    // past either end the end itself is closest
+    assert_eq!(
+        segment.closest_point(center, Vec2::new(40.0, 24.0)),
+        Vec2::new(20.0, 20.0)
+    );
+    assert_eq!(
+        segment.closest_point(center, Vec2::new(-30.0, 0.0)),
+        Vec2::new(0.0, 20.0)
+    );
+}
+
+#[test]
+fn border_modes() {
+    let mut universe = BoidUniverse::new(Vec2::splat(-100.0), Vec2::splat(100.0));
+    universe.border_margin = 20.0;
+    universe.border_force = 50.0;
+
+    // nothing until the margin, then growing towards the edge
+    let steer = |x: f32, y: f32| border_steering(&universe, Vec3::new(x, y, 0.0));
+    assert_eq!(steer(0.0, 0.0), Vec3::ZERO);
+    assert_eq!(steer(-80.0, 79.0), Vec3::ZERO);
+    assert_eq!(steer(-100.0, 0.0), Vec3::new(50.0, 0.0, 0.0));
+    assert_eq!(steer(-90.0, 0.0), Vec3::new(25.0, 0.0, 0.0));
+    assert_eq!(steer(95.0, 100.0), Vec3::new(-37.5, -50.0, 0.0));
+
+    let mut position = Vec3::new(110.0, -105.0, 0.0);
+    wrap_position(&universe, &mut position);
+    assert_eq!(position, Vec3::new(-90.0, 95.0, 0.0));
+    let mut position = Vec3::new(-350.0, 20.0, 0.0);
+    wrap_position(&universe, &mut position);
+    assert_eq!(position, Vec3::new(50.0, 20.0, 0.0));
+
+    let mut rng = SimRng::new(1);
+    let (mut position, mut velocity) = (Vec3::new(50.0, -100.0, 0.0), Vec3::new(3.0, 4.0, 0.0));
+    respawn_outside(&universe, &mut rng, &mut position, &mut velocity);
+    assert_eq!(position, Vec3::new(50.0, -100.0, 0.0));
+    assert_eq!(velocity, Vec3::new(3.0, 4.0, 0.0));
+
+    for _ in 0..20 {
+        let (mut position, mut velocity) = (Vec3::new(101.0, 0.0, 0.0), Vec3::new(3.0, 4.0, 0.0));
+        respawn_outside(&universe, &mut rng, &mut position, &mut velocity);
+        assert!(position.x.abs() <= 100.0 && position.y.abs() <= 100.0);
+        assert!((velocity.length() - 5.0).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn presets_blend_along_a_smoothstep() {
+    let mut universe = BoidUniverse::new(Vec2::splat(-100.0), Vec2::splat(100.0));
+    universe.drag = 0.0;
+    universe.min_speed = 40.0;
+    let mut presets = Presets::load_or_builtin();
+    presets.presets = vec![Preset {
+        name: "test".to_string(),
+        params: [("drag".to_string(), 1.0), ("unknown".to_string(), 5.0)]
+            .into_iter()
+            .collect(),
+    }];
+    presets.selected = 0;
+    presets.blend_seconds = 2.0;
+    presets.apply(&universe);
+
+    // eased in and out, a quarter of the time covers less than a quarter
+    let mut drag = vec![];
+    for delta in [0.5, 0.5, 0.5, 0.5] {
+        presets.advance(delta, &mut universe);
+        drag.push(universe.drag);
+    }
+    let expected = [0.15625, 0.5, 0.84375, 1.0];
+    drag.iter().zip(expected).for_each(|(drag, expected)| {
+        assert!((drag - expected).abs() < 1e-5, "{} != {}", drag, expected);
+    });
+    assert!(!presets.is_blending());
+    // params the preset leaves out stay as they are
+    assert_eq!(universe.min_speed, 40.0);
+
+    // without a duration the preset applies at once
+    presets.blend_seconds = 0.0;
+    universe.drag = 0.0;
+    presets.apply(&universe);
+    presets.advance(0.01, &mut universe);
+    assert_eq!(universe.drag, 1.0);
+}
+
+#[test]
+fn trail_wraps_around() {
+    let mut trail = Trail::default();
+    assert_eq!(trail.last(), None);
+
+    let total = MAX_TRAIL_LEN + 5;
+    (0..total).for_each(|i| trail.push(Vec2::splat(i as f32)));
+    assert_eq!(trail.last(), Some(Vec2::splat((total - 1) as f32)));
+
+    // newest first, the five oldest were overwritten
+    let recent: Vec<Vec2> = trail.recent(3).collect();
+    assert_eq!(
+        recent,
+        (total - 3..total)
+            .rev()
+            .map(|i| Vec2::splat(i as f32))
+            .collect::<Vec<_>>()
+    );
+    let all: Vec<Vec2> = trail.recent(usize::MAX).collect();
+    assert_eq!(all.len(), MAX_TRAIL_LEN);
+    assert_eq!(all.last(), Some(&Vec2::splat(5.0)));
+
+    trail.clear();
+    assert_eq!(trail.recent(usize::MAX).count(), 0);
+    trail.push(Vec2::ONE);
+    assert_eq!(
+        trail.recent(usize::MAX).collect::<Vec<_>>(),
+        vec![Vec2::ONE]
+    );
+}
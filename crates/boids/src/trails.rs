@@ -0,0 +1,77 @@
+//! fading lines behind boids, drawn from the last few positions
+
+use bevy::prelude::*;
+
+use crate::{
+    components::{Boid, Trail},
+    resources::{BoidUniverse, SelectedBoid, TrailSubset},
+};
+
+/// a step longer than this is a wrap or a respawn, the trail starts over
+const TRAIL_BREAK: f32 = 100.0;
+
+/// adds and removes `Trail`s so only the boids that should draw one
+/// keep positions around
+pub fn sync_trails(
+    mut commands: Commands,
+    boids: Query<(Entity, Has<Trail>), With<Boid>>,
+    universe: Res<BoidUniverse>,
+    selected: Res<SelectedBoid>,
+) {
+    boids.iter().for_each(|(entity, has_trail)| {
+        let wants_trail = universe.show_trails
+            && match universe.trail_subset {
+                TrailSubset::All => true,
+                TrailSubset::Selected => selected.0 == Some(entity),
+                TrailSubset::Sample => entity.index() % 10 == 0,
+            };
+
+        match (wants_trail, has_trail) {
+            (true, false) => {
+                commands.entity(entity).insert(Trail::default());
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Trail>();
+            }
+            _ => {}
+        }
+    });
+}
+
+pub fn record_trails(mut trails: Query<(&Transform, &mut Trail)>) {
+    trails.par_iter_mut().for_each(|(transform, mut trail)| {
+        let position = transform.translation.truncate();
+        match trail.last() {
+            // paused replays keep the boids in place
+            Some(last) if last == position => return,
+            Some(last) if last.distance(position) > TRAIL_BREAK => trail.clear(),
+            _ => {}
+        }
+        trail.push(position);
+    });
+}
+
+/// each trail in its boid's colour, fading out towards the oldest position
+pub fn render_trails(
+    universe: Res<BoidUniverse>,
+    trails: Query<(&Trail, &Handle<ColorMaterial>)>,
+    materials: Res<Assets<ColorMaterial>>,
+    mut gizmos: Gizmos,
+) {
+    if !universe.show_trails {
+        return;
+    }
+
+    trails.iter().for_each(|(trail, material)| {
+        let color = materials
+            .get(material)
+            .map(|material| material.color)
+            .unwrap_or(Color::WHITE);
+        let mut alpha = 1.0;
+        gizmos.linestrip_gradient_2d(trail.recent(universe.trail_length).map(|point| {
+            let faded = color.with_a(alpha);
+            alpha *= universe.trail_decay;
+            (point, faded)
+        }));
+    });
+}
@@ -0,0 +1,34 @@
+/// Summary of all values anchored inside a quad node.
+///
+/// Every value is anchored at the center of its region, so it counts towards
+/// exactly one node per tree level no matter how many quads its region touches.
+pub trait Aggregate<T>: Default + Clone {
+    fn add(&mut self, value: &T);
+    fn remove(&mut self, value: &T);
+    fn merge(&mut self, other: &Self);
+}
+
+/// no aggregation, the default for every tree
+impl<T> Aggregate<T> for () {
+    fn add(&mut self, _value: &T) {}
+    fn remove(&mut self, _value: &T) {}
+    fn merge(&mut self, _other: &Self) {}
+}
+
+/// number of values
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Count(pub usize);
+
+impl<T> Aggregate<T> for Count {
+    fn add(&mut self, _value: &T) {
+        self.0 += 1;
+    }
+
+    fn remove(&mut self, _value: &T) {
+        self.0 -= 1;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.0 += other.0;
+    }
+}
@@ -1,6 +1,4 @@
-use std::{
-    ops::{Add, Sub, Div},
-};
+use core::ops::{Add, Div, Sub};
 
 #[derive(Debug, Default, Copy, Clone, PartialEq)]
 pub struct Coord {
@@ -0,0 +1,20 @@
+//! A quadtree spatial index without any dependencies.
+//!
+//! Disable the default `std` feature to use it in `no_std` environments,
+//! only `alloc` is required.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+pub const MAX_CELL_SIZE: usize = 10;
+pub const MAX_DEPTH: usize = 7;
+
+pub mod aggregate;
+pub mod coord;
+pub mod region;
+pub mod tree;
+pub mod slot_map;
+
+mod node;
+#[cfg(test)]
+mod tests;
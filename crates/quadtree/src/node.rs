@@ -0,0 +1,245 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use super::{
+    aggregate::Aggregate,
+    region::Region,
+    slot_map::{SlotId, SlotMap},
+    MAX_CELL_SIZE, MAX_DEPTH,
+};
+
+/// everything a node needs to look up while restructuring
+pub struct NodeContext<'a, T> {
+    pub bounds: &'a Region,
+    pub region_store: &'a SlotMap<Region>,
+    pub value_store: &'a SlotMap<T>,
+}
+
+impl<'a, T> NodeContext<'a, T> {
+    fn region(&self, id: &SlotId) -> &'a Region {
+        self.region_store.get(id).unwrap()
+    }
+
+    fn value(&self, id: &SlotId) -> &'a T {
+        self.value_store.get(id).unwrap()
+    }
+
+    fn anchor(&self, id: &SlotId) -> (f32, f32) {
+        self.region(id).anchor_in(self.bounds)
+    }
+}
+
+#[derive(Debug)]
+enum NodeType<A> {
+    Leaf,
+    Parent(Box<[QuadNode<A>; 4]>),
+}
+
+#[derive(Debug)]
+pub struct QuadNode<A> {
+    region: Region,
+    values: Vec<SlotId>,
+    node_type: NodeType<A>,
+    depth: usize,
+    aggregate: A,
+}
+
+impl<A: Default + Clone> QuadNode<A> {
+    pub fn new(region: Region, depth: usize) -> Self {
+        Self {
+            values: Vec::new(),
+            region,
+            node_type: NodeType::Leaf,
+            depth,
+            aggregate: A::default(),
+        }
+    }
+
+    pub fn size(&self) -> &Region {
+        &self.region
+    }
+
+    pub fn aggregate(&self) -> &A {
+        &self.aggregate
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.node_type = NodeType::Leaf;
+        self.aggregate = A::default();
+    }
+
+    pub fn query(
+        &self,
+        region: &Region,
+        region_store: &SlotMap<Region>,
+        exclude: &Vec<SlotId>,
+    ) -> Vec<&SlotId> {
+        match &self.node_type {
+            NodeType::Leaf => self
+                .values
+                .iter()
+                .filter(|id| {
+                    region_store.get(id).unwrap().intersects(region) && !exclude.contains(id)
+                })
+                .collect(),
+            NodeType::Parent(children) => children
+                .iter()
+                .filter(|child| child.region.intersects(region))
+                .flat_map(|child| child.query(region, region_store, exclude))
+                .collect(),
+        }
+    }
+
+    /// combines all values anchored inside `region` into `result`,
+    /// nodes fully covered by `region` contribute their summary as a whole.
+    /// Both own their points half open, so a value on a shared edge is
+    /// counted the same either way
+    pub fn aggregate_region<T>(&self, region: &Region, ctx: &NodeContext<T>, result: &mut A)
+    where
+        A: Aggregate<T>,
+    {
+        if !self.region.intersects(region) {
+            return;
+        }
+
+        if region.contains(&self.region) {
+            result.merge(&self.aggregate);
+            return;
+        }
+
+        match &self.node_type {
+            NodeType::Leaf => self
+                .values
+                .iter()
+                .filter(|id| {
+                    let anchor = ctx.anchor(id);
+                    self.region.owns_point(anchor) && region.owns_point(anchor)
+                })
+                .for_each(|id| result.add(ctx.value(id))),
+            NodeType::Parent(children) => children
+                .iter()
+                .for_each(|child| child.aggregate_region(region, ctx, result)),
+        }
+    }
+
+    /// visits nodes top down, children are only entered if `visit` returns true
+    pub fn visit<F>(&self, visit: &mut F)
+    where
+        F: FnMut(&Region, &A, bool) -> bool,
+    {
+        let is_leaf = matches!(self.node_type, NodeType::Leaf);
+        if !visit(&self.region, &self.aggregate, is_leaf) {
+            return;
+        }
+
+        if let NodeType::Parent(children) = &self.node_type {
+            children.iter().for_each(|child| child.visit(visit));
+        }
+    }
+
+    pub fn get_regions(&self) -> Vec<&Region> {
+        match &self.node_type {
+            NodeType::Leaf => vec![&self.region],
+            NodeType::Parent(children) => children
+                .iter()
+                .flat_map(|child| child.get_regions())
+                .collect(),
+        }
+    }
+
+    /// a value lives in every child it touches and in the one owning its anchor
+    fn holds<T>(&self, value: &SlotId, ctx: &NodeContext<T>) -> bool {
+        self.region.intersects(ctx.region(value)) || self.region.owns_point(ctx.anchor(value))
+    }
+
+    pub fn remove<T>(&mut self, value: &SlotId, ctx: &NodeContext<T>)
+    where
+        A: Aggregate<T>,
+    {
+        if self.region.owns_point(ctx.anchor(value)) {
+            self.aggregate.remove(ctx.value(value));
+        }
+
+        match &mut self.node_type {
+            NodeType::Leaf => self.values.retain(|id| id != value),
+            NodeType::Parent(children) => {
+                children
+                    .iter_mut()
+                    .filter(|child| child.holds(value, ctx))
+                    .for_each(|child| child.remove(value, ctx));
+                // a child still split holds enough values on its own, checking
+                // that first keeps removals from counting the whole subtree
+                let all_leaves = children
+                    .iter()
+                    .all(|child| matches!(child.node_type, NodeType::Leaf));
+                if all_leaves && self.value_count_rec() < MAX_CELL_SIZE {
+                    // values spanning several children were drained more than once
+                    let mut values = self.drain_values_rec();
+                    values.sort();
+                    values.dedup();
+                    self.node_type = NodeType::Leaf;
+                    self.values.extend(values);
+                }
+            }
+        }
+    }
+
+    pub fn value_count_rec(&self) -> usize {
+        match &self.node_type {
+            NodeType::Leaf => self.values.len(),
+            NodeType::Parent(children) => children
+                .iter()
+                .fold(0, |acc, child| acc + child.value_count_rec()),
+        }
+    }
+
+    pub fn drain_values_rec(&mut self) -> Vec<SlotId> {
+        match &mut self.node_type {
+            NodeType::Leaf => self.values.drain(..).collect(),
+            NodeType::Parent(children) => children
+                .iter_mut()
+                .flat_map(|c| c.drain_values_rec())
+                .collect(),
+        }
+    }
+
+    pub fn insert<T>(&mut self, value: &SlotId, ctx: &NodeContext<T>)
+    where
+        A: Aggregate<T>,
+    {
+        if self.region.owns_point(ctx.anchor(value)) {
+            self.aggregate.add(ctx.value(value));
+        }
+
+        match &mut self.node_type {
+            NodeType::Leaf => {
+                self.values.push(value.clone());
+
+                if self.values.len() > MAX_CELL_SIZE && self.depth < MAX_DEPTH {
+                    self.node_type = NodeType::Parent(Box::new(
+                        self.region
+                            .quad_divide()
+                            .map(|reg| QuadNode::new(reg, self.depth + 1)),
+                    ));
+                    let ids: Vec<SlotId> = self.values.drain(..).collect();
+                    for id in ids {
+                        self.insert_children(&id, ctx);
+                    }
+                }
+            }
+            NodeType::Parent(_) => self.insert_children(value, ctx),
+        }
+    }
+
+    fn insert_children<T>(&mut self, value: &SlotId, ctx: &NodeContext<T>)
+    where
+        A: Aggregate<T>,
+    {
+        if let NodeType::Parent(children) = &mut self.node_type {
+            children
+                .iter_mut()
+                .filter(|child| child.holds(value, ctx))
+                .for_each(|child| child.insert(value, ctx));
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use super::coord::Coord;
+
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub min: Coord,
+    pub max: Coord,
+}
+
+impl Region {
+    pub fn new(min: Coord, max: Coord) -> Self {
+        Self { min, max }
+    }
+
+    pub fn with_margin(&self, margin: i32) -> Self {
+        Self::new(
+            Coord::new(self.min.x - margin, self.min.y - margin),
+            Coord::new(self.max.x + margin, self.max.y + margin),
+        )
+    }
+
+    pub fn intersects(&self, other: &Region) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+    }
+
+    pub fn contains(&self, other: &Region) -> bool {
+        self.min.x <= other.min.x
+            && self.max.x >= other.max.x
+            && self.min.y <= other.min.y
+            && self.max.y >= other.max.y
+    }
+
+    pub fn contains_point(&self, (x, y): (f32, f32)) -> bool {
+        self.min.x as f32 <= x
+            && self.max.x as f32 >= x
+            && self.min.y as f32 <= y
+            && self.max.y as f32 >= y
+    }
+
+    /// half open containment, sibling quads never share a point
+    pub fn owns_point(&self, (x, y): (f32, f32)) -> bool {
+        self.min.x as f32 <= x
+            && (self.max.x as f32) > x
+            && self.min.y as f32 <= y
+            && (self.max.y as f32) > y
+    }
+
+    /// center of `self` moved inside `bounds`, so it is owned by exactly one leaf
+    pub fn anchor_in(&self, bounds: &Region) -> (f32, f32) {
+        let (x, y) = self.center_f32();
+        (
+            x.max(bounds.min.x as f32).min(bounds.max.x as f32 - 0.25),
+            y.max(bounds.min.y as f32).min(bounds.max.y as f32 - 0.25),
+        )
+    }
+
+    pub fn size_f32(&self) -> (f32, f32) {
+        (
+            (self.max.x - self.min.x) as f32,
+            (self.max.y - self.min.y) as f32,
+        )
+    }
+
+    pub fn center_f32(&self) -> (f32, f32) {
+        (
+            (self.min.x + self.max.x) as f32 / 2.0,
+            (self.min.y + self.max.y) as f32 / 2.0,
+        )
+    }
+
+    pub fn center_distance(&self, other: &Region) -> f32 {
+        let (x1, y1) = self.center_f32();
+        let (x2, y2) = other.center_f32();
+        sqrt((x1 - x2) * (x1 - x2) + (y1 - y2) * (y1 - y2))
+    }
+
+    pub fn into_f32(&self) -> (f32, f32, f32, f32) {
+        (
+            self.min.x as f32,
+            self.min.y as f32,
+            self.max.x as f32,
+            self.max.y as f32,
+        )
+    }
+
+    pub fn quad_divide(&self) -> [Region; 4] {
+        // every quad shares the same center, odd sizes must not make them overlap
+        let center = self.min + (self.max - self.min) / 2;
+        [
+            Region::new(Coord::new(self.min.x, center.y), Coord::new(center.x, self.max.y)),
+            Region::new(center, self.max),
+            Region::new(self.min, center),
+            Region::new(Coord::new(center.x, self.min.y), Coord::new(self.max.x, center.y)),
+        ]
+    }
+}
+
+#[cfg(feature = "std")]
+fn sqrt(value: f32) -> f32 {
+    value.sqrt()
+}
+
+/// `f32::sqrt` lives in std, newton iterations are plenty for distance sorting
+#[cfg(not(feature = "std"))]
+fn sqrt(value: f32) -> f32 {
+    if value <= 0.0 {
+        return 0.0;
+    }
+    let mut guess = value;
+    for _ in 0..20 {
+        guess = 0.5 * (guess + value / guess);
+    }
+    guess
+}
@@ -0,0 +1,104 @@
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SlotId {
+    index: u64,
+}
+
+impl SlotId {
+    pub fn new(index: u64) -> Self {
+        Self { index }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SlotMap<T> {
+    data: Vec<Option<T>>,
+    id_stack: Vec<SlotId>,
+}
+
+impl<T> SlotMap<T> {
+    pub fn new() -> Self {
+        Self {
+            data: Vec::new(),
+            id_stack: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, value: T) -> SlotId {
+        match self.id_stack.pop() {
+            Some(id) => {
+                let cell = &mut self.data[id.index as usize];
+                *cell = Some(value);
+                id
+            }
+            None => {
+                let id = SlotId::new(self.data.len() as u64);
+                self.data.push(Some(value));
+                id
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len() - self.id_stack.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.id_stack.clear();
+    }
+
+    pub fn get(&self, id: &SlotId) -> Option<&T> {
+        self.data.get(id.index as usize).and_then(Option::as_ref)
+    }
+
+    pub fn get_mut(&mut self, id: &SlotId) -> Option<&mut T> {
+        self.data.get_mut(id.index as usize).and_then(Option::as_mut)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.data.iter().flatten()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut().flatten()
+    }
+
+    /// mutable access to several distinct slots at once,
+    /// duplicate and empty ids are skipped
+    pub fn get_many_mut<'a>(&mut self, ids: impl IntoIterator<Item = &'a SlotId>) -> Vec<&mut T> {
+        let mut indices: Vec<usize> = ids.into_iter().map(|id| id.index as usize).collect();
+        indices.sort_unstable();
+        indices.dedup();
+
+        // skips straight from one wanted slot to the next, so only the
+        // gaps are walked and not the whole map
+        let mut cells = self.data.iter_mut();
+        let mut next = 0;
+        indices
+            .into_iter()
+            .filter_map(|index| {
+                let cell = cells.nth(index - next)?;
+                next = index + 1;
+                cell.as_mut()
+            })
+            .collect()
+    }
+
+    pub fn update(&mut self, id: &SlotId, value: T) {
+        self.data[id.index as usize] = Some(value);
+    }
+
+    pub fn remove(&mut self, id: SlotId) -> Option<T> {
+        let value = self.data.get_mut(id.index as usize)?.take();
+        if value.is_some() {
+            self.id_stack.push(id);
+        }
+        value
+    }
+}
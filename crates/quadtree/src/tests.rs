@@ -0,0 +1,349 @@
+use alloc::{vec, vec::Vec};
+
+use super::{
+    aggregate::{Aggregate, Count},
+    coord::Coord,
+    region::Region,
+    slot_map::{SlotId, SlotMap},
+    tree::QuadTree,
+};
+
+macro_rules! region {
+    ($x:expr, $y:expr, $w:expr, $h:expr) => {
+        Region::new(Coord::new($x, $y), Coord::new($w, $h))
+    };
+}
+
+#[test]
+fn insert() {
+    let mut graph = QuadTree::<usize>::new(region!(0, 0, 10, 10));
+
+    let reg1 = region!(0, 0, 5, 5);
+    let reg2 = region!(5, 5, 10, 10);
+
+    graph.insert(reg1, 1);
+    graph.insert(reg2, 2);
+
+    assert_eq!(graph.value_count(), 2);
+
+    let exclude: Vec<SlotId> = vec![];
+    let query_region = region!(0, 0, 10, 10);
+    let result = graph.query(&query_region, &exclude);
+
+    assert_eq!(result.len(), 2);
+
+    let query_region = region!(0, 0, 4, 5);
+    let result = graph.query(&query_region, &exclude);
+
+    assert_eq!(result.len(), 1);
+}
+
+#[test]
+// kept as it was written before the crate split
+#[allow(unused_variables, clippy::clone_on_copy)]
+fn query() {
+    let mut graph = QuadTree::<usize>::new(region!(0, 0, 10, 10));
+
+    let reg1 = region!(0, 0, 5, 5);
+    let reg2 = region!(5, 5, 10, 10);
+    let reg3 = region!(4, 4, 6, 6);
+
+    let reg1_id = graph.insert(reg1, 1);
+    let reg2_id = graph.insert(reg2, 2);
+    let reg3_id = graph.insert(reg3, 3);
+
+    // test query
+    let exclude: Vec<SlotId> = vec![reg2_id];
+    let query_region = region!(0, 0, 10, 10);
+    let result = graph.query(&query_region, &exclude);
+    assert_eq!(result.len(), 2);
+
+    // test exclude
+    let query_region = region!(6, 6, 7, 7);
+    let exclude: Vec<SlotId> = vec![];
+    let result = graph.query(&query_region, &exclude);
+    assert_eq!(result.len(), 2);
+
+    // test value result
+    let query_region = region!(7, 7, 8, 8);
+    let exclude: Vec<SlotId> = vec![];
+    let result = graph.query(&query_region, &exclude);
+    assert_eq!(result.len(), 1);
+
+    let val = result[0].clone();
+    assert_eq!(val, 2)
+
+}
+
+#[test]
+fn query_mut() {
+    let mut graph = QuadTree::<usize>::new(region!(0, 0, 10, 10));
+
+    graph.insert(region!(0, 0, 5, 5), 1);
+    graph.insert(region!(5, 5, 10, 10), 2);
+    graph.insert(region!(4, 4, 6, 6), 3);
+
+    // spans every quad, values must only be visited once
+    let exclude: Vec<SlotId> = vec![];
+    let query_region = region!(0, 0, 10, 10);
+    let result = graph.query_mut(&query_region, &exclude);
+    assert_eq!(result.len(), 3);
+
+    graph.for_each_in_region_mut(&region!(6, 6, 7, 7), &exclude, |value| *value *= 10);
+
+    let mut result: Vec<usize> = graph
+        .query(&query_region, &exclude)
+        .into_iter()
+        .cloned()
+        .collect();
+    result.sort();
+    assert_eq!(result, vec![1, 20, 30]);
+}
+
+#[test]
+fn query_mut_split_nodes() {
+    let mut graph = QuadTree::<usize>::new(region!(0, 0, 100, 100));
+
+    // enough overlapping values to force subdivision
+    for i in 0..50 {
+        graph.insert(region!(40, 40, 60, 60), i);
+    }
+
+    let exclude: Vec<SlotId> = vec![];
+    graph.for_each_in_region_mut(&region!(0, 0, 100, 100), &exclude, |value| *value += 1);
+
+    let sum: usize = graph
+        .query(&region!(0, 0, 100, 100), &exclude)
+        .into_iter()
+        .sum();
+    assert_eq!(sum, (1..=50).sum::<usize>());
+}
+
+#[test]
+fn get_many_mut() {
+    let mut slots = SlotMap::new();
+    let ids: Vec<SlotId> = (0..10).map(|i| slots.insert(i)).collect();
+    slots.remove(ids[5].clone());
+
+    // unsorted, duplicated, removed and unknown ids
+    let wanted = vec![
+        ids[7].clone(),
+        ids[2].clone(),
+        ids[7].clone(),
+        ids[5].clone(),
+        SlotId::new(42),
+        ids[0].clone(),
+    ];
+    slots.get_many_mut(&wanted).into_iter().for_each(|value| *value += 100);
+
+    let values: Vec<i32> = slots.iter().cloned().collect();
+    assert_eq!(values, vec![100, 1, 102, 3, 4, 6, 107, 8, 9]);
+}
+
+#[test]
+fn query_hits() {
+    let mut graph = QuadTree::<usize>::new(region!(0, 0, 10, 10));
+
+    let reg1_id = graph.insert(region!(0, 0, 5, 5), 1);
+    graph.insert(region!(5, 5, 10, 10), 2);
+
+    let exclude: Vec<SlotId> = vec![];
+    let result = graph.query_hits(&region!(0, 0, 4, 4), &exclude);
+
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, reg1_id);
+    assert_eq!(*result[0].value, 1);
+    assert_eq!(result[0].region.max, Coord::new(5, 5));
+    assert!(result[0].distance.is_none());
+}
+
+#[test]
+fn query_nearest() {
+    let mut graph = QuadTree::<usize>::new(region!(0, 0, 100, 100));
+
+    graph.insert(region!(90, 90, 92, 92), 3);
+    graph.insert(region!(10, 10, 12, 12), 1);
+    graph.insert(region!(40, 40, 42, 42), 2);
+
+    let exclude: Vec<SlotId> = vec![];
+    let query_region = region!(0, 0, 20, 20);
+    let search_region = region!(0, 0, 100, 100);
+
+    // distances are measured from the center of the query region
+    let result = graph.query_nearest(&search_region, &exclude, None);
+    let values: Vec<usize> = result.iter().map(|hit| *hit.value).collect();
+    assert_eq!(values, vec![2, 1, 3]);
+
+    let result = graph.query_nearest(&query_region.with_margin(100), &exclude, Some(2));
+    let values: Vec<usize> = result.iter().map(|hit| *hit.value).collect();
+    assert_eq!(values, vec![1, 2]);
+    assert_eq!(result[0].distance, Some(1.0_f32.hypot(1.0)));
+}
+
+#[test]
+fn remove() {
+    let mut graph = QuadTree::<usize>::new(region!(0, 0, 100, 100));
+
+    let ids: Vec<SlotId> = (0..40)
+        .map(|i| graph.insert(region!(i * 2, i * 2, i * 2 + 4, i * 2 + 4), i as usize))
+        .collect();
+    assert_eq!(graph.value_count(), 40);
+
+    ids.iter().skip(5).for_each(|id| {
+        graph.remove(id);
+    });
+    assert_eq!(graph.value_count(), 5);
+
+    // removing twice is a no-op
+    assert_eq!(graph.remove(&ids[10]), None);
+    assert_eq!(graph.remove(&ids[0]), Some(0));
+
+    let exclude: Vec<SlotId> = vec![];
+    let mut result: Vec<usize> = graph
+        .query(&region!(0, 0, 100, 100), &exclude)
+        .into_iter()
+        .cloned()
+        .collect();
+    result.sort();
+    assert_eq!(result, vec![1, 2, 3, 4]);
+
+    // freed slots get reused
+    let id = graph.insert(region!(50, 50, 60, 60), 99);
+    assert_eq!(graph.value_count(), 5);
+    assert_eq!(graph.query(&region!(55, 55, 56, 56), &exclude), vec![&99]);
+    assert!(ids.contains(&id));
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Sum {
+    count: usize,
+    total: i64,
+}
+
+impl Aggregate<i64> for Sum {
+    fn add(&mut self, value: &i64) {
+        self.count += 1;
+        self.total += value;
+    }
+
+    fn remove(&mut self, value: &i64) {
+        self.count -= 1;
+        self.total -= value;
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.count += other.count;
+        self.total += other.total;
+    }
+}
+
+fn brute_force_sum(values: &[(Region, i64)], region: &Region) -> Sum {
+    values
+        .iter()
+        .filter(|(value_region, _)| region.owns_point(value_region.center_f32()))
+        .fold(Sum::default(), |mut sum, (_, value)| {
+            sum.add(value);
+            sum
+        })
+}
+
+#[test]
+fn aggregate() {
+    let mut graph = QuadTree::<usize, Count>::new(region!(0, 0, 10, 10));
+
+    graph.insert(region!(0, 0, 4, 4), 1);
+    graph.insert(region!(6, 6, 10, 10), 2);
+    // spans all four quads, still counted once
+    graph.insert(region!(4, 4, 6, 6), 3);
+
+    assert_eq!(graph.total(), &Count(3));
+    assert_eq!(graph.aggregate(&region!(0, 0, 10, 10)), Count(3));
+    assert_eq!(graph.aggregate(&region!(0, 0, 4, 4)), Count(1));
+    assert_eq!(graph.aggregate(&region!(0, 6, 4, 10)), Count(0));
+}
+
+#[test]
+fn aggregate_shared_edge() {
+    let mut graph = QuadTree::<usize, Count>::new(region!(0, 0, 10, 10));
+    for i in 0..20 {
+        graph.insert(region!(4, 4, 6, 6), i);
+    }
+
+    // centered on the corner the quads share, it belongs to the upper right one
+    assert_eq!(graph.aggregate(&region!(0, 0, 5, 5)), Count(0));
+    assert_eq!(graph.aggregate(&region!(5, 5, 10, 10)), Count(20));
+    assert_eq!(graph.aggregate(&region!(5, 5, 8, 8)), Count(20));
+    assert_eq!(graph.aggregate(&region!(0, 0, 10, 10)), Count(20));
+}
+
+#[test]
+fn aggregate_matches_brute_force() {
+    let mut graph = QuadTree::<i64, Sum>::new(region!(0, 0, 256, 256));
+    let mut values: Vec<(Region, i64)> = vec![];
+    let mut ids: Vec<SlotId> = vec![];
+
+    // small lcg, keeps the test deterministic
+    let mut seed: u32 = 7;
+    let mut next = |max: u32| {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+        ((seed >> 16) % max) as i32
+    };
+
+    for i in 0..500 {
+        let (x, y, size) = (next(250), next(250), next(6));
+        let region = region!(x, y, x + size, y + size);
+        ids.push(graph.insert(region.clone(), i));
+        values.push((region, i));
+    }
+
+    let queries = [
+        region!(0, 0, 256, 256),
+        region!(10, 10, 100, 100),
+        region!(64, 0, 128, 64),
+        region!(33, 71, 201, 190),
+    ];
+
+    queries.iter().for_each(|query| {
+        assert_eq!(graph.aggregate(query), brute_force_sum(&values, query));
+    });
+
+    // every other value removed, summaries must follow
+    ids.iter().step_by(2).for_each(|id| {
+        graph.remove(id);
+    });
+    let values: Vec<(Region, i64)> = values.into_iter().skip(1).step_by(2).collect();
+
+    queries.iter().for_each(|query| {
+        assert_eq!(graph.aggregate(query), brute_force_sum(&values, query));
+    });
+    assert_eq!(graph.total().count, 250);
+}
+
+#[test]
+fn visit() {
+    let mut graph = QuadTree::<usize, Count>::new(region!(0, 0, 100, 100));
+
+    for i in 0..20 {
+        graph.insert(region!(i, i, i + 1, i + 1), i as usize);
+    }
+
+    let mut leaves = 0;
+    let mut counted = 0;
+    graph.visit(|_, count, is_leaf| {
+        if is_leaf {
+            leaves += 1;
+            counted += count.0;
+        }
+        true
+    });
+    assert_eq!(leaves, graph.get_regions().len());
+    assert_eq!(counted, 20);
+
+    // stopping at the root only visits the root
+    let mut visited = 0;
+    graph.visit(|_, _, _| {
+        visited += 1;
+        false
+    });
+    assert_eq!(visited, 1);
+}
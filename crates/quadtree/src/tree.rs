@@ -0,0 +1,173 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use super::{
+    aggregate::Aggregate,
+    node::{NodeContext, QuadNode},
+    region::Region,
+    slot_map::{SlotId, SlotMap},
+};
+
+/// a single query result, keeps track of where the value lives in the tree
+#[derive(Debug)]
+pub struct QueryHit<'a, T> {
+    pub id: SlotId,
+    pub region: &'a Region,
+    pub value: &'a T,
+    /// distance between the region centers, only set by distance queries
+    pub distance: Option<f32>,
+}
+
+/// `A` keeps a summary per node, see [`Aggregate`]
+#[derive(Debug)]
+pub struct QuadTree<T, A = ()> {
+    bounds: Region,
+    region_store: SlotMap<Region>,
+    value_store: SlotMap<T>,
+    root: Box<QuadNode<A>>,
+}
+
+impl<T, A: Aggregate<T>> QuadTree<T, A> {
+    pub fn new(region: Region) -> Self {
+        Self {
+            bounds: region.clone(),
+            region_store: SlotMap::new(),
+            value_store: SlotMap::new(),
+            root: Box::new(QuadNode::new(region, 0)),
+        }
+    }
+
+    pub fn value_count(&self) -> usize {
+        self.value_store.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.region_store.clear();
+        self.value_store.clear();
+        self.root.clear();
+    }
+
+    pub fn query(&self, region: &Region, exclude: &Vec<SlotId>) -> Vec<&T> {
+        self.distinct_ids(region, exclude)
+            .into_iter()
+            .map(|id| self.value_store.get(id).unwrap())
+            .collect()
+    }
+
+    pub fn query_hits(&self, region: &Region, exclude: &Vec<SlotId>) -> Vec<QueryHit<'_, T>> {
+        self.distinct_ids(region, exclude)
+            .into_iter()
+            .map(|id| QueryHit {
+                region: self.region_store.get(id).unwrap(),
+                value: self.value_store.get(id).unwrap(),
+                id: id.clone(),
+                distance: None,
+            })
+            .collect()
+    }
+
+    /// hits sorted by distance to the center of the query region,
+    /// optionally limited to the closest `limit` results
+    pub fn query_nearest(
+        &self,
+        region: &Region,
+        exclude: &Vec<SlotId>,
+        limit: Option<usize>,
+    ) -> Vec<QueryHit<'_, T>> {
+        let mut hits = self.query_hits(region, exclude);
+        hits.iter_mut()
+            .for_each(|hit| hit.distance = Some(hit.region.center_distance(region)));
+        hits.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap());
+
+        if let Some(limit) = limit {
+            hits.truncate(limit);
+        }
+        hits
+    }
+
+    fn distinct_ids(&self, region: &Region, exclude: &Vec<SlotId>) -> Vec<&SlotId> {
+        let mut ids = self.root.query(region, &self.region_store, exclude);
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// values changed here are not reflected in the node aggregates,
+    /// remove and insert them again if the aggregate depends on them
+    pub fn query_mut(&mut self, region: &Region, exclude: &Vec<SlotId>) -> Vec<&mut T> {
+        let ids = self.root.query(region, &self.region_store, exclude);
+        self.value_store.get_many_mut(ids)
+    }
+
+    pub fn for_each_in_region_mut<F>(&mut self, region: &Region, exclude: &Vec<SlotId>, f: F)
+    where
+        F: FnMut(&mut T),
+    {
+        self.query_mut(region, exclude).into_iter().for_each(f);
+    }
+
+    /// combined aggregate of all values whose region center lies inside `region`,
+    /// its min edges included and its max edges not, see [`Region::owns_point`]
+    pub fn aggregate(&self, region: &Region) -> A {
+        let mut result = A::default();
+        self.root
+            .aggregate_region(region, &self.context(), &mut result);
+        result
+    }
+
+    /// aggregate of every value in the tree
+    pub fn total(&self) -> &A {
+        self.root.aggregate()
+    }
+
+    /// walks the nodes top down with their region, aggregate and whether they are a leaf,
+    /// returning false skips the children of that node
+    pub fn visit<F>(&self, mut visit: F)
+    where
+        F: FnMut(&Region, &A, bool) -> bool,
+    {
+        self.root.visit(&mut visit);
+    }
+
+    fn context(&self) -> NodeContext<'_, T> {
+        NodeContext {
+            bounds: &self.bounds,
+            region_store: &self.region_store,
+            value_store: &self.value_store,
+        }
+    }
+
+    pub fn size(&self) -> &Region {
+        self.root.size()
+    }
+
+    pub fn get_regions(&self) -> Vec<&Region> {
+        self.root.get_regions()
+    }
+
+    pub fn insert(&mut self, region: Region, values: T) -> SlotId {
+        let region_id = self.region_store.insert(region);
+        let value_id = self.value_store.insert(values);
+
+        assert!(region_id == value_id);
+
+        let ctx = NodeContext {
+            bounds: &self.bounds,
+            region_store: &self.region_store,
+            value_store: &self.value_store,
+        };
+        self.root.insert(&value_id, &ctx);
+        value_id
+    }
+
+    pub fn remove(&mut self, id: &SlotId) -> Option<T> {
+        self.region_store.get(id)?;
+        let ctx = NodeContext {
+            bounds: &self.bounds,
+            region_store: &self.region_store,
+            value_store: &self.value_store,
+        };
+        self.root.remove(id, &ctx);
+        self.region_store.remove(id.clone());
+        self.value_store.remove(id.clone())
+    }
+}
@@ -0,0 +1,151 @@
+//! runs the boid simulation without a window and prints timing and flock metrics
+//!
+//! cargo run --release --bin headless -- --boids 2000 --ticks 900 --seed 7
+
+use std::process::ExitCode;
+
+use bevy::prelude::Vec2;
+use boids::{
+    headless::{self, HeadlessConfig},
+    snapshot::Snapshot,
+};
+
+const USAGE: &str = "usage: headless [options]
+
+  --boids <n>            boids to spawn (default 1000)
+  --ticks <n>            physics ticks to run (default 900)
+  --seed <n>             rng seed (default 0)
+  --world-size <w>x<h>   world size (default 1280x720)
+  --params <file>        `name = value` lines overriding the defaults
+  --load <file>          start from a saved scene instead of random boids
+  --save <file>          save the scene after the last tick
+  --ecology              boids eat, breed and die";
+
+fn main() -> ExitCode {
+    let config = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match headless::run(&config) {
+        Ok(report) => {
+            println!("{}", report);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// `None` when asked for help
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<HeadlessConfig>, String> {
+    let mut config = HeadlessConfig::default();
+
+    while let Some(flag) = args.next() {
+        if flag == "-h" || flag == "--help" {
+            return Ok(None);
+        }
+        if flag == "--ecology" {
+            config.ecology = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value for {}", flag))?;
+        match flag.as_str() {
+            "--boids" => config.boids = parse(&flag, &value)?,
+            "--ticks" => config.ticks = parse(&flag, &value)?,
+            "--seed" => config.seed = parse(&flag, &value)?,
+            "--world-size" => {
+                let (width, height) = value
+                    .split_once('x')
+                    .ok_or_else(|| format!("--world-size expects <w>x<h>, got {}", value))?;
+                config.world_size = Vec2::new(parse(&flag, width)?, parse(&flag, height)?);
+                // random spawns need at least one unit either side of the origin
+                if !config.world_size.is_finite() || config.world_size.min_element() < 2.0 {
+                    return Err(format!("--world-size must be at least 2x2, got {}", value));
+                }
+            }
+            "--params" => {
+                let text = std::fs::read_to_string(&value)
+                    .map_err(|err| format!("can't read {}: {}", value, err))?;
+                config.params = headless::parse_params(&text)?;
+            }
+            "--load" => config.scene = Some(Snapshot::load(&value)?),
+            "--save" => config.save = Some(value),
+            _ => return Err(format!("unknown option {}", flag)),
+        }
+    }
+
+    Ok(Some(config))
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: {}", flag, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(str::to_string)
+    }
+
+    #[test]
+    fn defaults_and_help() {
+        let config = parse_args(args("")).unwrap().unwrap();
+        assert_eq!(config.boids, 1000);
+        assert_eq!(config.world_size, Vec2::new(1280.0, 720.0));
+        assert!(parse_args(args("--boids 5 --help")).unwrap().is_none());
+    }
+
+    #[test]
+    fn options() {
+        let config = parse_args(args(
+            "--boids 20 --ticks 3 --seed 7 --world-size 300x200 --ecology",
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.boids, 20);
+        assert_eq!(config.ticks, 3);
+        assert_eq!(config.seed, 7);
+        assert_eq!(config.world_size, Vec2::new(300.0, 200.0));
+        assert!(config.ecology);
+    }
+
+    #[test]
+    fn invalid() {
+        [
+            "--boids",
+            "--boids many",
+            "--frobnicate 1",
+            "--world-size 300",
+        ]
+        .iter()
+        .for_each(|line| assert!(parse_args(args(line)).is_err(), "{}", line));
+    }
+
+    #[test]
+    fn world_size_too_small() {
+        ["1x1", "0x100", "100x-5", "infx100", "NaNx100"]
+            .iter()
+            .for_each(|size| {
+                let line = format!("--world-size {}", size);
+                assert!(parse_args(args(&line)).is_err(), "{}", size);
+            });
+        assert!(parse_args(args("--world-size 2x2")).unwrap().is_some());
+    }
+}
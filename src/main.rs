@@ -1,14 +1,8 @@
-use bevy::{
-    core_pipeline::bloom::BloomSettings, diagnostic::FrameTimeDiagnosticsPlugin, prelude::*,
-    window::WindowResolution,
-};
+use bevy::{core_pipeline::bloom::BloomSettings, prelude::*, window::WindowResolution};
 use bevy_inspector_egui::bevy_egui::EguiPlugin;
 use boids::BoidPlugin;
 use wasm_bindgen::prelude::*;
 
-pub mod boids;
-pub mod quadtree;
-
 fn main() {
     run("#boids", 1280, 720);
 }
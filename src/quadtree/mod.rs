@@ -1,10 +0,0 @@
-pub const MAX_CELL_SIZE: usize = 10;
-pub const MAX_DEPTH: usize = 7;
-
-pub mod coord;
-pub mod region;
-pub mod tree;
-pub mod slot_map;
-
-mod node;
-mod tests;